@@ -1,14 +1,24 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
+
 use bevy_ecs::prelude::{Bundle, Component};
 use macroquad::{
     prelude::{vec2, Color, Rect, Vec2},
     text::Font,
     texture::Texture2D,
 };
-use macroquad_canvas::Canvas2D;
+use serde::{Deserialize, Serialize};
 
-use crate::utils::random;
+use crate::{
+    game::States,
+    teuria::{aseprite::SpriteSheet, asset::Handle},
+    utils::random,
+};
 pub mod resource;
 pub mod trigger;
+pub mod tween;
 
 // Game World
 #[derive(Clone, Copy, Component)]
@@ -22,13 +32,17 @@ pub struct Aabb(pub Rect);
 
 #[derive(Component)]
 pub struct TextureComponent {
-    pub texture: Texture2D,
+    pub texture: Handle<Texture2D>,
     pub source: Rect,
 }
 
 #[derive(Component)]
 pub struct ColorComponent(pub Color);
 
+/// Draw scale around the sprite's center, or the text's font scale.
+#[derive(Component)]
+pub struct Scale(pub f32);
+
 #[derive(Component)]
 pub struct Lives(pub i32);
 
@@ -41,14 +55,26 @@ pub enum Tags {
     Ball(BallType),
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum BallType {
     Original,
     Clone,
     Special,
 }
 
-#[derive(Clone, Copy)]
+impl BallType {
+    pub const ALL: [BallType; 3] = [BallType::Original, BallType::Clone, BallType::Special];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            BallType::Original => "Original",
+            BallType::Clone => "Clone",
+            BallType::Special => "Special",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum BlockType {
     Fragile,
     Cloner,
@@ -56,7 +82,320 @@ pub enum BlockType {
     Empty,
 }
 
-pub struct Resolution(pub Canvas2D);
+impl BlockType {
+    pub const ALL: [BlockType; 4] = [
+        BlockType::Fragile,
+        BlockType::Cloner,
+        BlockType::Special,
+        BlockType::Empty,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            BlockType::Fragile => "Fragile",
+            BlockType::Cloner => "Cloner",
+            BlockType::Special => "Special",
+            BlockType::Empty => "Empty",
+        }
+    }
+
+    pub const fn bounty(&self) -> i32 {
+        match self {
+            BlockType::Fragile => 50,
+            BlockType::Cloner => 30,
+            BlockType::Special => 100,
+            BlockType::Empty => 0,
+        }
+    }
+
+    pub const fn lives(&self) -> i32 {
+        match self {
+            BlockType::Fragile => 3,
+            BlockType::Cloner => 2,
+            BlockType::Special => 1,
+            BlockType::Empty => 0,
+        }
+    }
+
+    pub const fn pattern(&self) -> Option<PatternKind> {
+        match self {
+            BlockType::Cloner => Some(PatternKind::Stripes),
+            BlockType::Special => Some(PatternKind::Dots),
+            BlockType::Fragile | BlockType::Empty => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum PatternKind {
+    Stripes,
+    Dots,
+}
+
+// Trails
+#[derive(Clone, Copy)]
+pub enum TrailKind {
+    /// A tapering line through the recent positions.
+    Ribbon,
+    /// Separate dots at each recorded position.
+    Dots,
+}
+
+#[derive(Clone, Copy)]
+pub struct TrailStyle {
+    pub kind: TrailKind,
+    pub width: f32,
+    /// How long a recorded position stays visible, so faster balls leave longer trails.
+    pub duration: f32,
+}
+
+impl BallType {
+    pub const fn trail(&self) -> TrailStyle {
+        match self {
+            BallType::Original => TrailStyle {
+                kind: TrailKind::Ribbon,
+                width: 12.0,
+                duration: 0.12,
+            },
+            BallType::Clone => TrailStyle {
+                kind: TrailKind::Dots,
+                width: 8.0,
+                duration: 0.15,
+            },
+            BallType::Special => TrailStyle {
+                kind: TrailKind::Ribbon,
+                width: 20.0,
+                duration: 0.25,
+            },
+        }
+    }
+}
+
+/// Recent centers of a ball with their age in seconds, newest first.
+#[derive(Component)]
+pub struct Trail {
+    pub style: TrailStyle,
+    pub points: VecDeque<(Vec2, f32)>,
+}
+
+impl Trail {
+    pub fn new(style: TrailStyle) -> Self {
+        Trail {
+            style,
+            points: VecDeque::new(),
+        }
+    }
+}
+
+// Layers
+/// Draw order; higher values are drawn on top. Entities without one sit at `ZIndex::BOARD`.
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ZIndex(pub i32);
+
+impl ZIndex {
+    pub const BACKGROUND: ZIndex = ZIndex(-100);
+    pub const BOARD: ZIndex = ZIndex(0);
+    pub const BALLS: ZIndex = ZIndex(10);
+    pub const PARTICLES: ZIndex = ZIndex(20);
+    pub const UI: ZIndex = ZIndex(100);
+    pub const OVERLAY: ZIndex = ZIndex(200);
+    pub const OVERLAY_UI: ZIndex = ZIndex(210);
+}
+
+/// Drawn with the scene's fixed camera, so screen shake does not move it.
+#[derive(Component, Clone, Copy)]
+pub struct ScreenSpace;
+
+// Animation
+#[derive(Clone, Copy)]
+pub struct Frame {
+    pub source: Rect,
+    pub duration: f32,
+}
+
+#[derive(Clone)]
+pub struct AnimationClip {
+    pub frames: Vec<Frame>,
+    pub looping: bool,
+}
+
+impl AnimationClip {
+    /// Cuts frames out of a sheet laid out left to right, each shown for `duration` seconds.
+    pub fn strip(size: Vec2, frames: Range<usize>, duration: f32, looping: bool) -> Self {
+        AnimationClip {
+            frames: frames
+                .map(|i| Frame {
+                    source: Rect::new(i as f32 * size.x, 0.0, size.x, size.y),
+                    duration,
+                })
+                .collect(),
+            looping,
+        }
+    }
+
+    /// Takes the frames and durations of an Aseprite tag.
+    pub fn from_tag(sheet: &SpriteSheet, tag: &str, looping: bool) -> Option<Self> {
+        let tag = sheet.tags.iter().find(|t| t.name == tag)?;
+        let frames = tag
+            .frames
+            .iter()
+            .map(|&i| {
+                let (source, duration) = *sheet.frames.get(i)?;
+                Some(Frame { source, duration })
+            })
+            .collect::<Option<Vec<Frame>>>()?;
+        (!frames.is_empty()).then_some(AnimationClip { frames, looping })
+    }
+}
+
+/// Named clips for a sprite; `animation::system` writes the current frame into
+/// the entity's `TextureComponent.source`.
+#[derive(Component)]
+pub struct Animation {
+    clips: HashMap<&'static str, AnimationClip>,
+    current: &'static str,
+    frame: usize,
+    elapsed: f32,
+}
+
+impl Animation {
+    pub fn new(clips: Vec<(&'static str, AnimationClip)>, initial: &'static str) -> Self {
+        Animation {
+            clips: clips.into_iter().collect(),
+            current: initial,
+            frame: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Single-frame animation for sprites that do not move yet.
+    pub fn still(source: Rect) -> Self {
+        let clip = AnimationClip {
+            frames: vec![Frame {
+                source,
+                duration: f32::INFINITY,
+            }],
+            looping: false,
+        };
+        Animation::new(vec![("idle", clip)], "idle")
+    }
+
+    /// Switches to another clip from its first frame; playing the current clip does nothing.
+    pub fn play(&mut self, name: &'static str) {
+        if self.current != name && self.clips.contains_key(name) {
+            self.current = name;
+            self.frame = 0;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn advance(&mut self, delta: f32) {
+        let clip = &self.clips[self.current];
+        // A looping clip whose frames all last zero seconds would never catch up.
+        if clip.frames.iter().all(|frame| frame.duration <= 0.0) {
+            self.frame = clip.frames.len().saturating_sub(1);
+            self.elapsed = 0.0;
+            return;
+        }
+        self.elapsed += delta;
+        while self.elapsed >= clip.frames[self.frame].duration {
+            self.elapsed -= clip.frames[self.frame].duration;
+            if self.frame + 1 < clip.frames.len() {
+                self.frame += 1;
+            } else if clip.looping {
+                self.frame = 0;
+            } else {
+                // One-shot clips hold their last frame.
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+    }
+
+    pub fn source(&self) -> Rect {
+        self.clips[self.current].frames[self.frame].source
+    }
+}
+
+// Particles
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+    pub gravity: f32,
+    /// Starting color; the alpha fades out to zero over the lifetime.
+    pub color: Color,
+    /// Size at birth and at death, interpolated over the lifetime.
+    pub size: (f32, f32),
+}
+
+/// Describes a burst of particles. Ranges are `(min, max)` and sampled per particle.
+#[derive(Clone, Copy)]
+pub struct Emitter {
+    pub count: usize,
+    pub lifetime: (f32, f32),
+    pub speed: (f32, f32),
+    /// Angle in radians the burst is centered on.
+    pub direction: f32,
+    /// How far in radians a particle may deviate from `direction` either way.
+    pub spread: f32,
+    pub gravity: f32,
+    pub size: (f32, f32),
+}
+
+impl Emitter {
+    pub const DEBRIS: Emitter = Emitter {
+        count: 16,
+        lifetime: (0.4, 0.9),
+        speed: (60.0, 220.0),
+        direction: -std::f32::consts::FRAC_PI_2,
+        spread: std::f32::consts::PI,
+        gravity: 600.0,
+        size: (6.0, 1.0),
+    };
+
+    pub const SPARKS: Emitter = Emitter {
+        count: 8,
+        lifetime: (0.15, 0.35),
+        speed: (150.0, 300.0),
+        direction: -std::f32::consts::FRAC_PI_2,
+        spread: std::f32::consts::FRAC_PI_3,
+        gravity: 200.0,
+        size: (3.0, 0.5),
+    };
+
+    pub fn particle(&self, color: Color) -> Particle {
+        let angle = self.direction + random(-self.spread, self.spread);
+        let speed = random(self.speed.0, self.speed.1);
+        Particle {
+            velocity: Vec2::from_angle(angle) * speed,
+            age: 0.0,
+            lifetime: random(self.lifetime.0, self.lifetime.1),
+            gravity: self.gravity,
+            color,
+            size: self.size,
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct ParticleBundle {
+    position: Position,
+    particle: Particle,
+    z: ZIndex,
+}
+
+impl ParticleBundle {
+    pub fn new(position: Vec2, particle: Particle) -> Self {
+        ParticleBundle {
+            position: Position(position),
+            particle,
+            z: ZIndex::PARTICLES,
+        }
+    }
+}
+
 // UI
 #[derive(Component)]
 pub struct Text {
@@ -74,10 +413,97 @@ pub struct FontComponent {
 pub struct ScoreTag;
 
 #[derive(Component)]
-pub struct GameOverTag;
+pub struct LivesTag;
 
 #[derive(Component)]
-pub struct LivesTag;
+pub struct StateTag(pub States);
+
+#[derive(Component)]
+pub struct Overlay(pub Color);
+
+#[derive(Bundle)]
+pub struct OverlayBundle {
+    overlay: Overlay,
+    z: ZIndex,
+    screen: ScreenSpace,
+}
+
+impl OverlayBundle {
+    pub const fn new(color: Color) -> Self {
+        OverlayBundle {
+            overlay: Overlay(color),
+            z: ZIndex::OVERLAY,
+            screen: ScreenSpace,
+        }
+    }
+}
+
+/// A theme image stretched over the whole canvas.
+#[derive(Component)]
+pub struct Background(pub Handle<Texture2D>);
+
+#[derive(Bundle)]
+pub struct BackgroundBundle {
+    background: Background,
+    z: ZIndex,
+    screen: ScreenSpace,
+}
+
+impl BackgroundBundle {
+    pub const fn new(texture: Handle<Texture2D>) -> Self {
+        BackgroundBundle {
+            background: Background(texture),
+            z: ZIndex::BACKGROUND,
+            screen: ScreenSpace,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct NameLetter(pub usize);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Play,
+    Continue,
+    LevelSelect,
+    StartLevel(u32),
+    Options,
+    HighScores,
+    Statistics,
+    Quit,
+    Resume,
+    RestartLevel,
+    RetryLevel,
+    RestartCampaign,
+    QuitToTitle,
+    SaveAndQuit,
+    VideoOptions,
+    AudioOptions,
+    GameplayOptions,
+    ToggleFullscreen,
+    CycleResolution,
+    CycleScaleMode,
+    CycleBars,
+    CycleTheme,
+    AdjustMasterVolume,
+    AdjustSfxVolume,
+    AdjustMusicVolume,
+    ToggleMute,
+    ToggleReducedMotion,
+    ToggleTrails,
+    CyclePalette,
+    TogglePatterns,
+    CycleControls,
+    Back,
+}
+
+#[derive(Component)]
+pub struct MenuItem {
+    pub index: usize,
+    pub action: MenuAction,
+    pub enabled: bool,
+}
 
 #[derive(Bundle)]
 pub struct TextScoreBundle {
@@ -108,6 +534,9 @@ pub struct TextBundle {
     rect: Position,
     color: ColorComponent,
     font: FontComponent,
+    scale: Scale,
+    z: ZIndex,
+    screen: ScreenSpace,
 }
 
 impl TextBundle {
@@ -127,6 +556,9 @@ impl TextBundle {
             }),
             color: ColorComponent(color),
             font: FontComponent { font, size },
+            scale: Scale(1.0),
+            z: ZIndex::UI,
+            screen: ScreenSpace,
         }
     }
 }
@@ -138,10 +570,11 @@ pub struct PlayerBundle {
     lives: Lives,
     aabb: Aabb,
     texture: TextureComponent,
+    animation: Animation,
 }
 
 impl PlayerBundle {
-    pub const fn new(position: Vec2, color: Color, texture: Texture2D) -> Self {
+    pub fn new(position: Vec2, color: Color, texture: Handle<Texture2D>, source: Rect) -> Self {
         PlayerBundle {
             position: Position(position),
             color: ColorComponent(color),
@@ -152,15 +585,57 @@ impl PlayerBundle {
                 w: 150.0,
                 h: 8.0,
             }),
+            texture: TextureComponent { texture, source },
+            animation: Animation::still(source),
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct BlockBundle {
+    position: Position,
+    aabb: Aabb,
+    color: ColorComponent,
+    tags: Tags,
+    bounty: Bounty,
+    lives: Lives,
+    texture: TextureComponent,
+    animation: Animation,
+    scale: Scale,
+}
+
+impl BlockBundle {
+    pub fn new(
+        position: Vec2,
+        block_type: BlockType,
+        lives: i32,
+        color: Color,
+        texture: Handle<Texture2D>,
+        clips: &[(&'static str, AnimationClip)],
+    ) -> Self {
+        BlockBundle {
+            position: Position(position),
+            aabb: Aabb(Rect {
+                x: 0.0,
+                y: 0.0,
+                w: 100.0,
+                h: 40.0,
+            }),
+            color: ColorComponent(color),
+            tags: Tags::Block(block_type),
+            bounty: Bounty(block_type.bounty()),
+            lives: Lives(lives),
             texture: TextureComponent {
                 texture,
                 source: Rect {
                     x: 0.0,
                     y: 0.0,
-                    w: 150.0,
-                    h: 4.0,
+                    w: 100.0,
+                    h: 40.0,
                 },
             },
+            animation: Animation::new(clips.to_vec(), "intact"),
+            scale: Scale(1.0),
         }
     }
 }
@@ -173,11 +648,25 @@ pub struct BallBundle {
     velocity: Velocity,
     tags: Tags,
     texture: TextureComponent,
+    animation: Animation,
+    trail: Trail,
+    z: ZIndex,
 }
 
 impl BallBundle {
-    pub fn new(position: Vec2, color: Color, texture: Texture2D, ball_type: BallType) -> Self {
+    pub fn new(
+        position: Vec2,
+        color: Color,
+        texture: Handle<Texture2D>,
+        ball_type: BallType,
+    ) -> Self {
         let random_vel = vec2(random::<f32>(-1.0, 1.0).signum(), -1.0);
+        let source = Rect {
+            x: 0.0,
+            y: 0.0,
+            w: 30.0,
+            h: 30.0,
+        };
         BallBundle {
             position: Position(position),
             color: ColorComponent(color),
@@ -187,17 +676,12 @@ impl BallBundle {
                 w: 30.0,
                 h: 30.0,
             }),
-            texture: TextureComponent {
-                texture,
-                source: Rect {
-                    x: 0.0,
-                    y: 0.0,
-                    w: 30.0,
-                    h: 30.0,
-                },
-            },
+            texture: TextureComponent { texture, source },
+            animation: Animation::still(source),
             velocity: Velocity(random_vel),
             tags: Tags::Ball(ball_type),
+            trail: Trail::new(ball_type.trail()),
+            z: ZIndex::BALLS,
         }
     }
 }
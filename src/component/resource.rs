@@ -1,8 +1,332 @@
+use std::ops::Range;
+
 use bevy_ecs::prelude::Entity;
-use macroquad::{prelude::Rect, text::Font};
+use macroquad::{
+    audio::Sound,
+    prelude::{vec2, Color, Rect, BLACK, BLUE, GREEN, ORANGE, PINK, RED, WHITE, YELLOW},
+    text::Font,
+    texture::Texture2D,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{AnimationClip, BallType, BlockType};
+use crate::{
+    settings::PaletteKind,
+    teuria::{
+        asset::{AssetRegistry, Handle},
+        manifest::AssetErrors,
+    },
+    theme,
+};
 
 pub struct Score(pub i32);
+pub struct BestScore(pub i32);
 pub struct Time(pub f32);
 
+pub struct Level {
+    pub number: u32,
+    pub start_score: i32,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    pub blocks_broken: u32,
+    pub balls_lost: u32,
+    pub time_played: f32,
+    /// Paddle hits since the original ball was last lost.
+    pub rally: u32,
+    /// Blocks broken since the ball last touched the paddle.
+    pub combo: u32,
+}
+
+/// Upper bound on live particles; bursts are trimmed to fit.
+pub struct ParticleBudget(pub usize);
 pub struct ColliderResource(pub Vec<(Entity, Rect)>);
 pub struct FontResource(pub Font);
+pub struct MenuSelection(pub usize);
+
+pub struct NameEntry {
+    pub letters: [u8; 3],
+    pub cursor: usize,
+}
+
+impl Default for NameEntry {
+    fn default() -> Self {
+        NameEntry {
+            letters: *b"AAA",
+            cursor: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SoundEvent {
+    PaddleHit,
+    WallBounce,
+    BlockDamaged,
+    BlockDestroyed(BlockType),
+    BallLost(BallType),
+    LevelClear,
+}
+
+impl SoundEvent {
+    /// Block hits without their own sample fall back to the original hit sound;
+    /// other events stay silent, as they were before they had sounds.
+    pub fn sample(&self, sounds: &SoundHandles) -> Option<Handle<Sound>> {
+        let block_hit = match self {
+            SoundEvent::PaddleHit => return sounds.paddle_hit,
+            SoundEvent::WallBounce => return sounds.wall_bounce,
+            SoundEvent::BallLost(BallType::Original) => return sounds.lose_life,
+            SoundEvent::BallLost(_) => return sounds.ball_lost,
+            SoundEvent::LevelClear => return sounds.level_clear,
+            SoundEvent::BlockDamaged => sounds.block_damage,
+            SoundEvent::BlockDestroyed(BlockType::Cloner) => sounds.break_cloner,
+            SoundEvent::BlockDestroyed(BlockType::Special) => sounds.break_special,
+            SoundEvent::BlockDestroyed(_) => sounds.break_fragile,
+        };
+        Some(block_hit.unwrap_or(sounds.hit_block))
+    }
+}
+
+/// Gameplay events raised this frame, turned into sounds by `audio::event_system`.
+pub struct SoundEvents(pub Vec<SoundEvent>);
+
+/// Textures the game draws, looked up once so a missing manifest key fails at startup.
+#[derive(Clone, Copy)]
+pub struct TextureHandles {
+    pub player: Handle<Texture2D>,
+    pub blocks: Handle<Texture2D>,
+    pub ball: Handle<Texture2D>,
+    pub background: Option<Handle<Texture2D>>,
+    pub bars: Option<Handle<Texture2D>>,
+}
+
+impl TextureHandles {
+    /// Keys the builder checks for at startup.
+    pub const REQUIRED: [&'static str; 3] = ["Player", "Blocks", "Ball"];
+
+    pub fn resolve(registry: &AssetRegistry) -> Result<Self, AssetErrors> {
+        let textures = &registry.textures;
+        let mut errors = Vec::new();
+        let [player, blocks, ball] = Self::REQUIRED.map(|key| textures.require(key, &mut errors));
+        match (player, blocks, ball) {
+            (Some(player), Some(blocks), Some(ball)) => Ok(TextureHandles {
+                player,
+                blocks,
+                ball,
+                background: textures.handle("Background"),
+                bars: textures.handle("Bars"),
+            }),
+            _ => Err(AssetErrors(errors)),
+        }
+    }
+}
+
+/// Where sprites sit in their sheets. Aseprite sources can override the fixed layout:
+/// `intact`, `cracked` and `shattered` tags in the blocks file and a `Paddle` slice in the
+/// player file.
+pub struct SpriteLayout {
+    pub block_clips: Vec<(&'static str, AnimationClip)>,
+    pub paddle: Rect,
+}
+
+impl SpriteLayout {
+    pub fn resolve(registry: &AssetRegistry) -> Self {
+        let sheet = |key: &str| {
+            let handle = registry.sheets.handle(key)?;
+            Some(&registry.sheets[handle])
+        };
+        let blocks = sheet("Blocks");
+        let size = vec2(100.0, 40.0);
+        let clip = |name: &str, frames: Range<usize>, duration: f32| {
+            blocks
+                .and_then(|sheet| AnimationClip::from_tag(sheet, name, false))
+                .unwrap_or_else(|| AnimationClip::strip(size, frames, duration, false))
+        };
+        let paddle = sheet("Player")
+            .and_then(|sheet| sheet.slices.get("Paddle").copied())
+            .unwrap_or_else(|| Rect::new(0.0, 0.0, 150.0, 4.0));
+        SpriteLayout {
+            block_clips: vec![
+                ("intact", clip("intact", 0..1, f32::INFINITY)),
+                ("cracked", clip("cracked", 1..3, 0.08)),
+                ("shattered", clip("shattered", 3..5, 0.08)),
+            ],
+            paddle,
+        }
+    }
+}
+
+/// Sound effects and music tracks; only `hit_block` is required.
+#[derive(Clone, Copy)]
+pub struct SoundHandles {
+    pub hit_block: Handle<Sound>,
+    pub paddle_hit: Option<Handle<Sound>>,
+    pub wall_bounce: Option<Handle<Sound>>,
+    pub block_damage: Option<Handle<Sound>>,
+    pub break_fragile: Option<Handle<Sound>>,
+    pub break_cloner: Option<Handle<Sound>>,
+    pub break_special: Option<Handle<Sound>>,
+    pub lose_life: Option<Handle<Sound>>,
+    pub ball_lost: Option<Handle<Sound>>,
+    pub level_clear: Option<Handle<Sound>>,
+    pub title_music: Option<Handle<Sound>>,
+    pub gameplay_music: Option<Handle<Sound>>,
+    pub boss_music: Option<Handle<Sound>>,
+    pub game_over_music: Option<Handle<Sound>>,
+}
+
+impl SoundHandles {
+    /// Keys the builder checks for at startup.
+    pub const REQUIRED: [&'static str; 1] = ["HitBlock"];
+
+    pub fn resolve(registry: &AssetRegistry) -> Result<Self, AssetErrors> {
+        let sounds = &registry.sounds;
+        let mut errors = Vec::new();
+        let [hit_block] = Self::REQUIRED;
+        let hit_block = sounds
+            .require(hit_block, &mut errors)
+            .ok_or(AssetErrors(errors))?;
+        Ok(SoundHandles {
+            hit_block,
+            paddle_hit: sounds.handle("PaddleHit"),
+            wall_bounce: sounds.handle("WallBounce"),
+            block_damage: sounds.handle("BlockDamage"),
+            break_fragile: sounds.handle("BreakFragile"),
+            break_cloner: sounds.handle("BreakCloner"),
+            break_special: sounds.handle("BreakSpecial"),
+            lose_life: sounds.handle("LoseLife"),
+            ball_lost: sounds.handle("BallLost"),
+            level_clear: sounds.handle("LevelClear"),
+            title_music: sounds.handle("TitleMusic"),
+            gameplay_music: sounds.handle("GameplayMusic"),
+            boss_music: sounds.handle("BossMusic"),
+            game_over_music: sounds.handle("GameOverMusic"),
+        })
+    }
+}
+
+/// Every gameplay color goes through here so the board can be recolored for color vision
+/// deficiencies or high contrast.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub fragile: Color,
+    pub cloner: Color,
+    pub special: Color,
+    pub empty: Color,
+    /// Blocks with more lives than any block starts with.
+    pub healthy: Color,
+    /// Blocks down to two lives.
+    pub damaged: Color,
+    /// Blocks one hit away from breaking.
+    pub critical: Color,
+    pub ball: Color,
+    pub clone_ball: Color,
+    pub special_ball: Color,
+    pub player: Color,
+    /// Drawn over blocks when `patterns` is set, so block types differ by more than color.
+    pub pattern: Color,
+    pub patterns: bool,
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0)
+}
+
+impl Palette {
+    pub fn new(kind: PaletteKind, patterns: bool) -> Self {
+        let theme = theme::active().palette();
+        let pick = |color: Option<[u8; 3]>, fallback| color.map_or(fallback, theme::color);
+        let base = Palette {
+            fragile: pick(theme.fragile, RED),
+            cloner: pick(theme.cloner, BLUE),
+            special: pick(theme.special, PINK),
+            empty: BLACK,
+            healthy: pick(theme.healthy, GREEN),
+            damaged: pick(theme.damaged, BLUE),
+            critical: pick(theme.critical, ORANGE),
+            ball: pick(theme.ball, WHITE),
+            clone_ball: pick(theme.clone_ball, BLUE),
+            special_ball: pick(theme.special_ball, PINK),
+            player: pick(theme.player, WHITE),
+            pattern: Color::new(0.0, 0.0, 0.0, 0.45),
+            patterns,
+        };
+        // The theme only sets the default palette; the color-blind ones use the Okabe-Ito set.
+        match kind {
+            PaletteKind::Default => base,
+            PaletteKind::Deuteranopia => Palette {
+                fragile: rgb(213, 94, 0),
+                cloner: rgb(0, 114, 178),
+                special: rgb(204, 121, 167),
+                healthy: rgb(240, 228, 66),
+                damaged: rgb(86, 180, 233),
+                critical: rgb(230, 159, 0),
+                clone_ball: rgb(86, 180, 233),
+                special_ball: rgb(204, 121, 167),
+                ..base
+            },
+            PaletteKind::Protanopia => Palette {
+                fragile: rgb(230, 159, 0),
+                cloner: rgb(0, 114, 178),
+                special: rgb(204, 121, 167),
+                healthy: rgb(240, 228, 66),
+                damaged: rgb(86, 180, 233),
+                critical: rgb(255, 255, 255),
+                clone_ball: rgb(86, 180, 233),
+                special_ball: rgb(204, 121, 167),
+                ..base
+            },
+            PaletteKind::Tritanopia => Palette {
+                fragile: rgb(213, 94, 0),
+                cloner: rgb(0, 158, 115),
+                special: rgb(204, 121, 167),
+                healthy: rgb(0, 158, 115),
+                damaged: rgb(120, 120, 120),
+                critical: rgb(230, 60, 60),
+                clone_ball: rgb(0, 200, 150),
+                special_ball: rgb(204, 121, 167),
+                ..base
+            },
+            PaletteKind::HighContrast => Palette {
+                fragile: WHITE,
+                cloner: rgb(0, 255, 255),
+                special: rgb(255, 0, 255),
+                healthy: rgb(0, 255, 0),
+                damaged: YELLOW,
+                critical: rgb(255, 80, 0),
+                ball: YELLOW,
+                clone_ball: rgb(0, 255, 255),
+                special_ball: rgb(255, 0, 255),
+                player: YELLOW,
+                pattern: Color::new(0.0, 0.0, 0.0, 0.8),
+                ..base
+            },
+        }
+    }
+
+    /// Color of a block of the given type with this many lives left.
+    pub fn block(&self, block_type: BlockType, lives: i32) -> Color {
+        match lives {
+            // Untouched blocks show their type.
+            lives if lives == block_type.lives() => match block_type {
+                BlockType::Fragile => self.fragile,
+                BlockType::Cloner => self.cloner,
+                BlockType::Special => self.special,
+                BlockType::Empty => self.empty,
+            },
+            2 => self.damaged,
+            1 => self.critical,
+            _ => self.healthy,
+        }
+    }
+
+    pub fn ball(&self, ball_type: BallType) -> Color {
+        match ball_type {
+            BallType::Original => self.ball,
+            BallType::Clone => self.clone_ball,
+            BallType::Special => self.special_ball,
+        }
+    }
+}
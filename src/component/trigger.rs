@@ -1,31 +1,53 @@
-use std::collections::HashMap;
-
-use bevy_ecs::{query::Without, system::Command};
-use macroquad::{
-    prelude::{vec2, Vec2, WHITE},
-    text::Font,
-    texture::Texture2D,
+use bevy_ecs::{
+    prelude::Entity,
+    query::{With, Without},
+    system::Command,
 };
+use macroquad::prelude::{vec2, warn, Color, Vec2, WHITE, YELLOW};
 
-use crate::game::WIDTH;
+use crate::{
+    game::{States, WIDTH},
+    system::state,
+};
 
-use super::{BallBundle, GameOverTag, Lives, Position, Tags, TextBundle};
+use super::{
+    resource::{
+        BestScore, FontResource, Level, MenuSelection, NameEntry, Palette, ParticleBudget,
+        RunStats, Score, TextureHandles,
+    },
+    tween::{Easing, Tween, TweenTarget},
+    BallBundle, BallType, Emitter, Lives, MenuAction, MenuItem, NameLetter, OverlayBundle,
+    Particle, ParticleBundle, Position, StateTag, Tags, TextBundle, ZIndex,
+};
 
 pub struct AddPlayer;
 pub struct AddBall(pub BallBundle);
-pub struct AddGameOverText(pub Font);
+pub struct AddGameOverSummary;
+pub struct AddNameEntry;
 pub struct AddPlayerLives(pub i32);
+pub struct ChangeState(pub States);
+/// Despawns every block and ball.
+pub struct ClearBoard;
+pub struct RestartLevel;
+pub struct RestartCampaign;
+pub struct EmitParticles {
+    pub emitter: Emitter,
+    pub position: Vec2,
+    pub color: Color,
+}
+pub struct CompleteTween(pub Entity, pub fn(Entity, &mut bevy_ecs::world::World));
+pub struct AddMenu {
+    pub position: Vec2,
+    pub overlay: bool,
+    pub state: Option<States>,
+    pub items: Vec<(String, MenuAction, bool)>,
+}
 
 impl Command for AddPlayer {
     fn write(self, world: &mut bevy_ecs::world::World) {
         let mut query = world.query_filtered::<(&Position, &mut Lives), Without<Tags>>();
-        let textures = {
-            world
-                .get_resource::<HashMap<String, Texture2D>>()
-                .unwrap()
-                .to_owned()
-        };
-        let textures = textures.get("Ball").unwrap();
+        let texture = world.resource::<TextureHandles>().ball;
+        let color = world.resource::<Palette>().ball(BallType::Original);
         let result = { query.get_single_mut(world) };
         if let Ok((pos, mut lives)) = result {
             let pos = Vec2::new(pos.0.x + 60.0, pos.0.y - 20.0);
@@ -33,12 +55,9 @@ impl Command for AddPlayer {
             if lives.0 <= 0 {
                 return;
             }
-            world.spawn().insert_bundle(BallBundle::new(
-                pos,
-                WHITE,
-                *textures,
-                super::BallType::Original,
-            ));
+            world
+                .spawn()
+                .insert_bundle(BallBundle::new(pos, color, texture, BallType::Original));
         }
     }
 }
@@ -49,19 +68,131 @@ impl Command for AddBall {
     }
 }
 
-impl Command for AddGameOverText {
+impl Command for AddGameOverSummary {
     fn write(self, world: &mut bevy_ecs::world::World) {
+        let font = world.resource::<FontResource>().0;
+        let score = world.resource::<Score>().0;
+        let level = world.resource::<Level>().number;
+        let stats = *world.resource::<RunStats>();
+        let mut best = world.resource_mut::<BestScore>();
+        let new_best = score > best.0;
+        if new_best {
+            best.0 = score;
+        }
+
+        let seconds = stats.time_played as u32;
+        let mut lines = vec![
+            (format!("Score: {score}"), WHITE),
+            (format!("Blocks Broken: {}", stats.blocks_broken), WHITE),
+            (format!("Balls Lost: {}", stats.balls_lost), WHITE),
+            (format!("Level Reached: {level}"), WHITE),
+            (
+                format!("Time Played: {}:{:02}", seconds / 60, seconds % 60),
+                WHITE,
+            ),
+        ];
+        if new_best {
+            lines.push(("New Personal Best!".into(), YELLOW));
+        }
+
+        world
+            .spawn()
+            .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.7)))
+            .insert(StateTag(States::GameOver));
         world
             .spawn()
             .insert_bundle(TextBundle::new(
                 "Game Over!".into(),
                 true,
-                vec2(WIDTH / 1.6, 40.0),
+                vec2(WIDTH / 2.0, 110.0),
                 WHITE,
-                30,
-                self.0,
+                48,
+                font,
             ))
-            .insert(GameOverTag);
+            .insert(Tween::new(
+                0.8,
+                Easing::BounceOut,
+                TweenTarget::Position(vec2(WIDTH / 2.0, -20.0), vec2(WIDTH / 2.0, 110.0)),
+            ))
+            .insert(ZIndex::OVERLAY_UI)
+            .insert(StateTag(States::GameOver));
+        for (i, (line, color)) in lines.into_iter().enumerate() {
+            world
+                .spawn()
+                .insert_bundle(TextBundle::new(
+                    line,
+                    true,
+                    vec2(WIDTH / 2.0, 170.0 + i as f32 * 32.0),
+                    color,
+                    24,
+                    font,
+                ))
+                .insert(ZIndex::OVERLAY_UI)
+                .insert(StateTag(States::GameOver));
+        }
+        AddMenu {
+            position: vec2(WIDTH / 2.0, 390.0),
+            overlay: true,
+            state: Some(States::GameOver),
+            items: vec![
+                ("Retry Level".into(), MenuAction::RetryLevel, true),
+                ("Restart Campaign".into(), MenuAction::RestartCampaign, true),
+                ("Quit".into(), MenuAction::QuitToTitle, true),
+            ],
+        }
+        .write(world);
+    }
+}
+
+impl Command for AddNameEntry {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        let font = world.resource::<FontResource>().0;
+        let score = world.resource::<Score>().0;
+        world.insert_resource(NameEntry::default());
+
+        world
+            .spawn()
+            .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.7)))
+            .insert(StateTag(States::NameEntry));
+        let lines = [
+            ("New High Score!".to_string(), 48, 160.0),
+            (format!("Score: {score}"), 30, 220.0),
+            ("Enter your name".to_string(), 24, 280.0),
+            (
+                "Up/Down to change, Left/Right to move, Enter to confirm".to_string(),
+                20,
+                440.0,
+            ),
+        ];
+        for (line, size, y) in lines {
+            world
+                .spawn()
+                .insert_bundle(TextBundle::new(
+                    line,
+                    true,
+                    vec2(WIDTH / 2.0, y),
+                    WHITE,
+                    size,
+                    font,
+                ))
+                .insert(ZIndex::OVERLAY_UI)
+                .insert(StateTag(States::NameEntry));
+        }
+        for index in 0..3 {
+            world
+                .spawn()
+                .insert_bundle(TextBundle::new(
+                    "A".into(),
+                    true,
+                    vec2(WIDTH / 2.0 + (index as f32 - 1.0) * 50.0, 360.0),
+                    WHITE,
+                    48,
+                    font,
+                ))
+                .insert(NameLetter(index))
+                .insert(ZIndex::OVERLAY_UI)
+                .insert(StateTag(States::NameEntry));
+        }
     }
 }
 
@@ -74,3 +205,114 @@ impl Command for AddPlayerLives {
         }
     }
 }
+
+impl Command for ChangeState {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        let current = *world.resource::<States>();
+        if !current.can_transition_to(self.0) {
+            warn!(
+                "Ignoring invalid state change {:?} -> {:?}",
+                current, self.0
+            );
+            return;
+        }
+        state::exit(world, current);
+        *world.resource_mut::<States>() = self.0;
+        state::enter(world, self.0);
+    }
+}
+
+impl Command for AddMenu {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        let font = world.resource::<FontResource>().0;
+        let mut selected = None;
+        for (index, (text, action, enabled)) in self.items.into_iter().enumerate() {
+            if enabled && selected.is_none() {
+                selected = Some(index);
+            }
+            let position = self.position + vec2(0.0, index as f32 * 45.0);
+            let mut entity = world.spawn();
+            entity
+                .insert_bundle(TextBundle::new(
+                    text,
+                    true,
+                    position,
+                    Color { a: 0.0, ..WHITE },
+                    30,
+                    font,
+                ))
+                .insert(MenuItem {
+                    index,
+                    action,
+                    enabled,
+                })
+                .insert(
+                    Tween::delay(index as f32 * 0.05)
+                        .then(
+                            0.25,
+                            Easing::CubicOut,
+                            TweenTarget::Position(position + vec2(0.0, 20.0), position),
+                        )
+                        .with(TweenTarget::Alpha(0.0, 1.0)),
+                );
+            if self.overlay {
+                entity.insert(ZIndex::OVERLAY_UI);
+            }
+            if let Some(state) = self.state {
+                entity.insert(StateTag(state));
+            }
+        }
+        world.insert_resource(MenuSelection(selected.unwrap_or(0)));
+    }
+}
+
+impl Command for EmitParticles {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        let budget = world.resource::<ParticleBudget>().0;
+        let live = world.query::<&Particle>().iter(world).count();
+        let count = self.emitter.count.min(budget.saturating_sub(live));
+        let particles = (0..count)
+            .map(|_| ParticleBundle::new(self.position, self.emitter.particle(self.color)))
+            .collect::<Vec<ParticleBundle>>();
+        world.spawn_batch(particles);
+    }
+}
+
+impl Command for CompleteTween {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        if world.get_entity(self.0).is_some() {
+            (self.1)(self.0, world);
+        }
+    }
+}
+
+impl Command for ClearBoard {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        let mut query = world.query_filtered::<Entity, With<Tags>>();
+        let board = query.iter(world).collect::<Vec<Entity>>();
+        for e in board {
+            world.despawn(e);
+        }
+    }
+}
+
+impl Command for RestartLevel {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        ClearBoard.write(world);
+        let start_score = world.resource::<Level>().start_score;
+        world.resource_mut::<Score>().0 = start_score;
+    }
+}
+
+impl Command for RestartCampaign {
+    fn write(self, world: &mut bevy_ecs::world::World) {
+        ClearBoard.write(world);
+        AddPlayerLives(3).write(world);
+        world.resource_mut::<Score>().0 = 0;
+        world.insert_resource(Level {
+            number: 1,
+            start_score: 0,
+        });
+        world.insert_resource(RunStats::default());
+    }
+}
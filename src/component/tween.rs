@@ -0,0 +1,169 @@
+use std::{collections::VecDeque, f32::consts::PI};
+
+use bevy_ecs::{
+    prelude::{Component, Entity},
+    world::World,
+};
+use macroquad::prelude::Vec2;
+
+/// Standard easing curves mapping linear progress in `0..=1` to eased progress.
+#[derive(Clone, Copy)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicOut,
+    BackOut,
+    ElasticOut,
+    BounceOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) * 0.5
+                }
+            }
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Easing::BounceOut => {
+                let (n1, d1) = (7.5625, 2.75);
+                if t < 1.0 / d1 {
+                    n1 * t * t
+                } else if t < 2.0 / d1 {
+                    let t = t - 1.5 / d1;
+                    n1 * t * t + 0.75
+                } else if t < 2.5 / d1 {
+                    let t = t - 2.25 / d1;
+                    n1 * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / d1;
+                    n1 * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/// A property a tween drives. Targets whose component is missing on the entity are skipped.
+#[derive(Clone, Copy)]
+pub enum TweenTarget {
+    Position(Vec2, Vec2),
+    Alpha(f32, f32),
+    Scale(f32, f32),
+    TextSize(u16, u16),
+}
+
+pub struct TweenStep {
+    pub duration: f32,
+    pub easing: Easing,
+    pub targets: Vec<TweenTarget>,
+}
+
+/// Plays steps one after another; the targets within a step run together.
+#[derive(Component)]
+pub struct Tween {
+    steps: VecDeque<TweenStep>,
+    elapsed: f32,
+    on_complete: Option<fn(Entity, &mut World)>,
+}
+
+impl Tween {
+    pub fn new(duration: f32, easing: Easing, target: TweenTarget) -> Self {
+        Tween {
+            steps: VecDeque::from([TweenStep {
+                duration,
+                easing,
+                targets: vec![target],
+            }]),
+            elapsed: 0.0,
+            on_complete: None,
+        }
+    }
+
+    /// Waits before the following steps start.
+    pub fn delay(duration: f32) -> Self {
+        Tween {
+            steps: VecDeque::from([TweenStep {
+                duration,
+                easing: Easing::Linear,
+                targets: Vec::new(),
+            }]),
+            elapsed: 0.0,
+            on_complete: None,
+        }
+    }
+
+    /// Adds a target that runs alongside the last step.
+    pub fn with(mut self, target: TweenTarget) -> Self {
+        if let Some(step) = self.steps.back_mut() {
+            step.targets.push(target);
+        }
+        self
+    }
+
+    pub fn then(mut self, duration: f32, easing: Easing, target: TweenTarget) -> Self {
+        self.steps.push_back(TweenStep {
+            duration,
+            easing,
+            targets: vec![target],
+        });
+        self
+    }
+
+    /// Runs through `Commands` once the last step has finished.
+    pub fn on_complete(mut self, callback: fn(Entity, &mut World)) -> Self {
+        self.on_complete = Some(callback);
+        self
+    }
+
+    /// Advances the tween, returning the active targets with their eased progress.
+    /// Steps skipped over in one frame are still reported at their end value.
+    pub fn advance(&mut self, delta: f32) -> Vec<(TweenTarget, f32)> {
+        let mut values = Vec::new();
+        self.elapsed += delta;
+        while let Some(step) = self.steps.front() {
+            if self.elapsed < step.duration {
+                let t = step.easing.apply(self.elapsed / step.duration);
+                values.extend(step.targets.iter().map(|target| (*target, t)));
+                break;
+            }
+            self.elapsed -= step.duration;
+            values.extend(step.targets.iter().map(|target| (*target, 1.0)));
+            self.steps.pop_front();
+        }
+        values
+    }
+
+    pub fn finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn callback(&self) -> Option<fn(Entity, &mut World)> {
+        self.on_complete
+    }
+}
+
+pub fn despawn(entity: Entity, world: &mut World) {
+    world.despawn(entity);
+}
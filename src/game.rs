@@ -1,32 +1,42 @@
 use bevy_ecs::{
-    schedule::{Schedule, ShouldRun, Stage, StageLabel, SystemStage},
-    system::{Commands, Res},
+    schedule::{Schedule, ShouldRun, StageLabel, SystemStage},
+    system::{Command, Res},
 };
-use macroquad::{
-    audio::Sound,
-    prelude::{vec2, Vec2, WHITE},
-    text::Font,
-    texture::Texture2D,
-};
-use macroquad_canvas::Canvas2D;
-use std::collections::HashMap;
+use macroquad::prelude::{vec2, warn, Color, GRAY, WHITE};
 
 use crate::{
     component::{
-        resource::{ColliderResource, FontResource, Score, Time},
-        BallBundle, BallType, LivesTag, PlayerBundle, Resolution, TextBundle, TextScoreBundle,
+        resource::{
+            BestScore, ColliderResource, Level, MenuSelection, NameEntry, Palette, ParticleBudget,
+            RunStats, Score, SoundEvents, SoundHandles, SpriteLayout, TextureHandles,
+        },
+        trigger::AddMenu,
+        BackgroundBundle, BallType, BlockType, MenuAction, OverlayBundle, TextBundle,
     },
+    highscore::{GameMode, HighScores},
+    save::SaveGame,
+    settings::{self, Settings},
+    statistics::Statistics,
     system::*,
+    teuria::{
+        camera::CameraEffects,
+        scene::{Assets, SceneFactory, WorldScene},
+    },
 };
 
 pub const WIDTH: f32 = 800f32;
 pub const HEIGHT: f32 = 560f32;
+/// Live particle cap for the gameplay scene.
+pub const MAX_PARTICLES: usize = 400;
+/// Most levels the level select lists, counting back from the highest one reached.
+const LEVEL_SELECT_ENTRIES: u32 = 8;
 
 pub type GameResult<T> = anyhow::Result<T>;
 
 pub trait World {
     fn start(&mut self) -> GameResult<()>;
     fn update(&mut self, delta: f32) -> GameResult<()>;
+    fn should_quit(&self) -> bool;
 }
 
 #[derive(StageLabel)]
@@ -35,69 +45,37 @@ enum Stages {
     PreUpdate,
     Update,
     PostUpdate,
-    PreDraw,
     Draw,
-    PostDraw,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum States {
     Playing,
+    NameEntry,
     GameOver,
     Restarting,
     Reviving,
-    Paused,
-}
-
-pub struct WorldScene {
-    pub world: bevy_ecs::world::World,
-    pub schedule: Schedule,
-    pub font: Option<Font>,
-    pub score: i32,
-}
-
-impl WorldScene {
-    pub fn new(
-        font: Option<Font>,
-        resolution: Canvas2D,
-        textures: HashMap<String, Texture2D>,
-        audios: HashMap<String, Sound>,
-    ) -> Self {
-        let schedule = Schedule::default();
-        let mut world = bevy_ecs::world::World::new();
-        world.insert_resource::<HashMap<String, Texture2D>>(textures);
-        world.insert_resource::<HashMap<String, Sound>>(audios);
-        world.insert_resource::<Score>(Score(0));
-        world.insert_resource::<Time>(Time(0.0));
-        world.insert_resource::<States>(States::Reviving);
-        world.insert_resource::<Resolution>(Resolution(resolution));
-        world.insert_resource::<ColliderResource>(ColliderResource(Vec::new()));
-        world.insert_resource::<FontResource>(FontResource(font.unwrap()));
-        WorldScene {
-            world,
-            schedule,
-            font,
-            score: 0,
-        }
+}
+
+impl States {
+    pub fn can_transition_to(&self, next: States) -> bool {
+        matches!(
+            (self, next),
+            (States::Reviving, States::Playing)
+                | (States::Restarting, States::Playing)
+                | (States::Playing, States::Restarting)
+                | (States::Playing, States::Reviving)
+                | (States::Playing, States::NameEntry)
+                | (States::Playing, States::GameOver)
+                | (States::NameEntry, States::GameOver)
+                | (States::GameOver, States::Reviving)
+        )
     }
 }
 
-fn restart(
-    mut command: Commands,
-    textures: Res<HashMap<String, Texture2D>>,
-    states: Res<States>,
-) -> ShouldRun {
+fn restart(states: Res<States>) -> ShouldRun {
     match *states {
-        States::Restarting => ShouldRun::Yes,
-        States::Reviving => {
-            command.spawn().insert_bundle(BallBundle::new(
-                Vec2::new(180.0, 480.0),
-                WHITE,
-                textures["Ball"],
-                BallType::Original,
-            ));
-            ShouldRun::Yes
-        }
+        States::Restarting | States::Reviving => ShouldRun::Yes,
         _ => ShouldRun::No,
     }
 }
@@ -110,87 +88,568 @@ fn is_playing(states: Res<States>) -> ShouldRun {
     }
 }
 
-impl World for WorldScene {
-    fn start(&mut self) -> GameResult<()> {
-        self.schedule
-            .add_stage(
-                Stages::Init,
-                Schedule::default()
-                    .with_run_criteria(restart)
-                    .with_stage(Stages::Init, SystemStage::parallel()),
-            )
-            .add_stage(
-                Stages::PreUpdate,
-                Schedule::default()
-                    .with_run_criteria(is_playing)
-                    .with_stage(Stages::PreUpdate, SystemStage::parallel())
-                    .with_stage(Stages::Update, SystemStage::parallel())
-                    .with_stage(Stages::PostUpdate, SystemStage::parallel()),
+pub fn title_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.world.insert_resource::<Settings>(Settings::load());
+    scene.on_resume = Some(settings::reload);
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system)
+        .add_system_to_stage(Stages::Update, audio::system)
+        .add_system_to_stage(Stages::Update, viewport::system)
+        .add_system_to_stage(Stages::Update, audio::title_music_system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    spawn_background(&mut scene.world);
+    scene.world.spawn().insert_bundle(TextBundle::new(
+        "Breaker".into(),
+        true,
+        vec2(WIDTH / 2.0, 150.0),
+        WHITE,
+        72,
+        assets.font,
+    ));
+    AddMenu {
+        position: vec2(WIDTH / 2.0, 230.0),
+        overlay: false,
+        state: None,
+        items: vec![
+            ("Continue".into(), MenuAction::Continue, SaveGame::exists()),
+            ("Play".into(), MenuAction::Play, true),
+            (
+                "Level Select".into(),
+                MenuAction::LevelSelect,
+                Statistics::load().highest_level > 1,
+            ),
+            ("Options".into(), MenuAction::Options, true),
+            ("High Scores".into(), MenuAction::HighScores, true),
+            ("Statistics".into(), MenuAction::Statistics, true),
+            ("Quit".into(), MenuAction::Quit, true),
+        ],
+    }
+    .write(&mut scene.world);
+    scene
+}
+
+pub fn gameplay_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    let world = &mut scene.world;
+    world.insert_resource::<Score>(Score(0));
+    let highscores = HighScores::load();
+    world.insert_resource::<BestScore>(BestScore(highscores.best(GameMode::Campaign)));
+    world.insert_resource::<HighScores>(highscores);
+    world.insert_resource::<GameMode>(GameMode::Campaign);
+    world.insert_resource::<RunStats>(RunStats::default());
+    world.insert_resource::<Statistics>(Statistics::load());
+    world.insert_resource::<Level>(Level {
+        number: 1,
+        start_score: 0,
+    });
+    world.insert_resource::<States>(States::Reviving);
+    world.insert_resource::<ColliderResource>(ColliderResource(Vec::new()));
+    // Replaced by `AddMenu` and `AddNameEntry` when they open; their systems run from the first frame.
+    world.insert_resource::<MenuSelection>(MenuSelection(0));
+    world.insert_resource::<NameEntry>(NameEntry::default());
+    world.insert_resource::<SoundEvents>(SoundEvents(Vec::new()));
+    world.insert_resource::<ParticleBudget>(ParticleBudget(MAX_PARTICLES));
+    world.insert_resource::<CameraEffects>(CameraEffects::default());
+    let settings = Settings::load();
+    world.insert_resource::<Palette>(Palette::new(settings.palette, settings.block_patterns));
+    world.insert_resource::<Settings>(settings);
+    scene.on_resume = Some(settings::reload);
+
+    scene
+        .schedule
+        .add_stage(
+            Stages::Init,
+            Schedule::default()
+                .with_run_criteria(restart)
+                .with_stage(Stages::Init, SystemStage::parallel()),
+        )
+        .add_stage(
+            Stages::PreUpdate,
+            Schedule::default()
+                .with_run_criteria(is_playing)
+                .with_stage(Stages::PreUpdate, SystemStage::parallel())
+                .with_stage(Stages::Update, SystemStage::parallel())
+                .with_stage(Stages::PostUpdate, SystemStage::parallel()),
+        )
+        .add_stage(Stages::Update, SystemStage::parallel());
+
+    scene
+        .schedule
+        .stage(Stages::Init, |schedule: &mut Schedule| {
+            schedule.add_system_to_stage(Stages::Init, block::init_system)
+        })
+        .stage(Stages::PreUpdate, |schedule: &mut Schedule| {
+            schedule.add_system_to_stage(Stages::PreUpdate, colliders::query_all_colliders);
+            schedule.add_system_to_stage(Stages::PreUpdate, stats::time_system);
+            schedule.add_system_to_stage(Stages::Update, ball::system);
+            schedule.add_system_to_stage(Stages::Update, player::system);
+            schedule.add_system_to_stage(Stages::Update, block::system);
+            schedule.add_system_to_stage(Stages::PostUpdate, colliders::system)
+        })
+        .add_system_to_stage(Stages::Update, text::lives_system)
+        .add_system_to_stage(Stages::Update, text::score_system)
+        .add_system_to_stage(Stages::Update, menu::system)
+        .add_system_to_stage(Stages::Update, highscore::name_entry_system)
+        .add_system_to_stage(Stages::Update, pause::system)
+        .add_system_to_stage(Stages::Update, audio::system)
+        .add_system_to_stage(Stages::Update, audio::event_system)
+        .add_system_to_stage(Stages::Update, particle::system)
+        .add_system_to_stage(Stages::Update, animation::system)
+        .add_system_to_stage(Stages::Update, trail::system)
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, camera::system)
+        .add_system_to_stage(Stages::Update, palette::system)
+        .add_system_to_stage(Stages::Update, audio::gameplay_music_system);
+
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    spawn_background(&mut scene.world);
+    state::spawn_player(&mut scene.world);
+    state::enter(&mut scene.world, States::Reviving);
+    scene
+}
+
+/// Starts a fresh run at the level picked in the level select.
+pub fn level_scene(number: u32) -> SceneFactory {
+    Box::new(move |assets| {
+        let mut scene = gameplay_scene(assets);
+        scene.world.insert_resource::<Level>(Level {
+            number,
+            start_score: 0,
+        });
+        scene
+    })
+}
+
+pub fn level_select_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.overlay = true;
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    let world = &mut scene.world;
+    world
+        .spawn()
+        .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.9)));
+    world.spawn().insert_bundle(TextBundle::new(
+        "Level Select".into(),
+        true,
+        vec2(WIDTH / 2.0, 60.0),
+        WHITE,
+        40,
+        assets.font,
+    ));
+
+    let highest = Statistics::load().highest_level.max(1);
+    let first = highest.saturating_sub(LEVEL_SELECT_ENTRIES - 1).max(1);
+    let mut items = (first..=highest)
+        .map(|number| {
+            (
+                format!("Level {number}"),
+                MenuAction::StartLevel(number),
+                true,
             )
-            .add_stage(Stages::Update, SystemStage::parallel())
-            .add_stage(Stages::PreDraw, SystemStage::single_threaded())
-            .add_stage(Stages::Draw, SystemStage::single_threaded())
-            .add_stage(Stages::PostDraw, SystemStage::single_threaded());
-
-        self.schedule
-            .stage(Stages::Init, |schedule: &mut Schedule| {
-                schedule.add_system_to_stage(Stages::Init, block::init_system)
-            })
-            .stage(Stages::PreUpdate, |schedule: &mut Schedule| {
-                schedule.add_system_to_stage(Stages::PreUpdate, colliders::query_all_colliders);
-                schedule.add_system_to_stage(Stages::Update, ball::system);
-                schedule.add_system_to_stage(Stages::Update, player::system);
-                schedule.add_system_to_stage(Stages::Update, block::system);
-                schedule.add_system_to_stage(Stages::PostUpdate, colliders::system)
-            })
-            .add_system_to_stage(Stages::Update, text::lives_system)
-            .add_system_to_stage(Stages::Update, text::score_system)
-            .add_system_to_stage(Stages::Update, text::gameover_system)
-            .add_system_to_stage(Stages::Update, pause::system)
-            .add_system_to_stage(Stages::PreDraw, draw::pre_system)
-            .add_system_to_stage(Stages::Draw, text::system)
-            .add_system_to_stage(Stages::Draw, draw::system)
-            .add_system_to_stage(Stages::PostDraw, draw::post_system);
-
-        let textures = {
-            self.world
-                .get_resource::<HashMap<String, Texture2D>>()
-                .unwrap()
-                .to_owned()
-        };
-
-        self.world.spawn().insert_bundle(TextScoreBundle::new(
-            "Score".into(),
-            false,
-            vec2(25.0, 40.0),
-            WHITE,
-            30u16,
-            self.font.unwrap(),
+        })
+        .collect::<Vec<(String, MenuAction, bool)>>();
+    items.push(("Back".into(), MenuAction::Back, true));
+    AddMenu {
+        position: vec2(WIDTH / 2.0, 120.0),
+        overlay: false,
+        state: None,
+        items,
+    }
+    .write(world);
+    scene
+}
+
+/// Starts gameplay from the saved run, falling back to a fresh run if it cannot be read.
+pub fn continue_scene(assets: &Assets) -> WorldScene {
+    let mut scene = gameplay_scene(assets);
+    if let Some(save) = SaveGame::load() {
+        save.restore(&mut scene.world);
+    }
+    if let Err(e) = SaveGame::delete() {
+        warn!("Failed to delete saved run: {}", e);
+    }
+    scene
+}
+
+pub fn pause_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.overlay = true;
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system)
+        .add_system_to_stage(Stages::Update, pause::resume_system)
+        .add_system_to_stage(Stages::Update, audio::duck_system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    scene
+        .world
+        .spawn()
+        .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.6)));
+    scene.world.spawn().insert_bundle(TextBundle::new(
+        "Paused".into(),
+        true,
+        vec2(WIDTH / 2.0, 200.0),
+        WHITE,
+        48,
+        assets.font,
+    ));
+    AddMenu {
+        position: vec2(WIDTH / 2.0, 270.0),
+        overlay: false,
+        state: None,
+        items: vec![
+            ("Resume".into(), MenuAction::Resume, true),
+            ("Restart Level".into(), MenuAction::RestartLevel, true),
+            ("Options".into(), MenuAction::Options, true),
+            ("Quit to Title".into(), MenuAction::QuitToTitle, true),
+            ("Save and Quit".into(), MenuAction::SaveAndQuit, true),
+        ],
+    }
+    .write(&mut scene.world);
+    scene
+}
+
+/// A scene with the game's asset handles; they are checked once at startup.
+fn new_scene(assets: &Assets) -> WorldScene {
+    let mut scene = WorldScene::new(assets);
+    let textures = TextureHandles::resolve(&assets.registry).expect("textures checked at startup");
+    let sounds = SoundHandles::resolve(&assets.registry).expect("sounds checked at startup");
+    scene.world.insert_resource::<TextureHandles>(textures);
+    scene.world.insert_resource::<SoundHandles>(sounds);
+    scene
+        .world
+        .insert_resource(SpriteLayout::resolve(&assets.registry));
+    scene
+}
+
+/// Picks up tags and slices from reloaded Aseprite files, and assets newly added to
+/// the manifest under keys the game looks for.
+#[cfg(feature = "dev")]
+pub fn reload_handles(world: &mut bevy_ecs::world::World) {
+    let registry = world.resource::<crate::teuria::asset::AssetRegistry>();
+    let layout = SpriteLayout::resolve(registry);
+    let textures = TextureHandles::resolve(registry);
+    let sounds = SoundHandles::resolve(registry);
+    world.insert_resource(layout);
+    // Both resolve as long as the required keys were loaded at startup.
+    if let (Ok(textures), Ok(sounds)) = (textures, sounds) {
+        world.insert_resource(textures);
+        world.insert_resource(sounds);
+    }
+}
+
+/// Adds the theme's background, if it has one.
+fn spawn_background(world: &mut bevy_ecs::world::World) {
+    if let Some(texture) = world.resource::<TextureHandles>().background {
+        world.spawn().insert_bundle(BackgroundBundle::new(texture));
+    }
+}
+
+pub fn options_scene(assets: &Assets) -> WorldScene {
+    options_page(
+        assets,
+        "Options",
+        vec![
+            ("Video".into(), MenuAction::VideoOptions, true),
+            ("Audio".into(), MenuAction::AudioOptions, true),
+            ("Gameplay".into(), MenuAction::GameplayOptions, true),
+            ("Back".into(), MenuAction::Back, true),
+        ],
+        None,
+    )
+}
+
+pub fn video_options_scene(assets: &Assets) -> WorldScene {
+    options_page(
+        assets,
+        "Video",
+        vec![
+            ("Window".into(), MenuAction::ToggleFullscreen, true),
+            ("Resolution".into(), MenuAction::CycleResolution, true),
+            ("Scaling".into(), MenuAction::CycleScaleMode, true),
+            ("Bars".into(), MenuAction::CycleBars, true),
+            ("Theme".into(), MenuAction::CycleTheme, true),
+            ("Back".into(), MenuAction::Back, true),
+        ],
+        Some("Window and theme changes apply after a restart"),
+    )
+}
+
+pub fn audio_options_scene(assets: &Assets) -> WorldScene {
+    options_page(
+        assets,
+        "Audio",
+        vec![
+            ("Master Volume".into(), MenuAction::AdjustMasterVolume, true),
+            ("SFX Volume".into(), MenuAction::AdjustSfxVolume, true),
+            ("Music Volume".into(), MenuAction::AdjustMusicVolume, true),
+            ("Sound".into(), MenuAction::ToggleMute, true),
+            ("Back".into(), MenuAction::Back, true),
+        ],
+        None,
+    )
+}
+
+pub fn gameplay_options_scene(assets: &Assets) -> WorldScene {
+    options_page(
+        assets,
+        "Gameplay",
+        vec![
+            ("Controls".into(), MenuAction::CycleControls, true),
+            (
+                "Reduced Motion".into(),
+                MenuAction::ToggleReducedMotion,
+                true,
+            ),
+            ("Ball Trails".into(), MenuAction::ToggleTrails, true),
+            ("Palette".into(), MenuAction::CyclePalette, true),
+            ("Block Patterns".into(), MenuAction::TogglePatterns, true),
+            ("Back".into(), MenuAction::Back, true),
+        ],
+        None,
+    )
+}
+
+/// Builds one page of the options menu; every page edits and saves its own copy of the settings.
+fn options_page(
+    assets: &Assets,
+    title: &str,
+    items: Vec<(String, MenuAction, bool)>,
+    footer: Option<&str>,
+) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.overlay = true;
+    scene.world.insert_resource::<Settings>(Settings::load());
+    scene.on_resume = Some(settings::reload);
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_stage(Stages::PostUpdate, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system)
+        .add_system_to_stage(Stages::PostUpdate, options::system)
+        .add_system_to_stage(Stages::PostUpdate, audio::system)
+        .add_system_to_stage(Stages::PostUpdate, viewport::system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    scene
+        .world
+        .spawn()
+        .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.9)));
+    scene.world.spawn().insert_bundle(TextBundle::new(
+        title.into(),
+        true,
+        vec2(WIDTH / 2.0, 80.0),
+        WHITE,
+        48,
+        assets.font,
+    ));
+    AddMenu {
+        position: vec2(WIDTH / 2.0, 150.0),
+        overlay: false,
+        state: None,
+        items,
+    }
+    .write(&mut scene.world);
+    if let Some(footer) = footer {
+        scene.world.spawn().insert_bundle(TextBundle::new(
+            footer.into(),
+            true,
+            vec2(WIDTH / 2.0, 520.0),
+            GRAY,
+            20,
+            assets.font,
         ));
+    }
+    scene
+}
 
-        self.world
-            .spawn()
-            .insert_bundle(TextBundle::new(
-                "Lives".into(),
-                false,
-                vec2(WIDTH / 1.2, 40.0),
-                WHITE,
-                30u16,
-                self.font.unwrap(),
-            ))
-            .insert(LivesTag);
-        self.world.spawn().insert_bundle(PlayerBundle::new(
-            Vec2::new(120.0, 500.0),
+pub fn highscores_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.overlay = true;
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
+
+    let world = &mut scene.world;
+    world
+        .spawn()
+        .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.9)));
+    let highscores = HighScores::load();
+    let mut y = 80.0;
+    for mode in GameMode::ALL {
+        world.spawn().insert_bundle(TextBundle::new(
+            format!("High Scores - {}", mode.name()),
+            true,
+            vec2(WIDTH / 2.0, y),
             WHITE,
-            textures["Player"],
+            40,
+            assets.font,
         ));
-        Ok(())
+        y += 45.0;
+        let entries = highscores.entries(mode);
+        if entries.is_empty() {
+            world.spawn().insert_bundle(TextBundle::new(
+                "No scores yet".into(),
+                true,
+                vec2(WIDTH / 2.0, y),
+                GRAY,
+                24,
+                assets.font,
+            ));
+            y += 32.0;
+        }
+        for (rank, entry) in entries.iter().enumerate() {
+            let columns = [
+                (format!("{}.", rank + 1), 220.0),
+                (entry.name.clone(), 270.0),
+                (entry.score.to_string(), 390.0),
+                (format!("Level {}", entry.level), 520.0),
+            ];
+            for (column, x) in columns {
+                world.spawn().insert_bundle(TextBundle::new(
+                    column,
+                    false,
+                    vec2(x, y),
+                    WHITE,
+                    24,
+                    assets.font,
+                ));
+            }
+            y += 32.0;
+        }
+    }
+    AddMenu {
+        position: vec2(WIDTH / 2.0, HEIGHT - 40.0),
+        overlay: false,
+        state: None,
+        items: vec![("Back".into(), MenuAction::Back, true)],
     }
+    .write(world);
+    scene
+}
+
+pub fn statistics_scene(assets: &Assets) -> WorldScene {
+    let mut scene = new_scene(assets);
+    scene.overlay = true;
+    scene
+        .schedule
+        .add_stage(Stages::Update, SystemStage::parallel())
+        .add_system_to_stage(Stages::Update, tween::system)
+        .add_system_to_stage(Stages::Update, menu::system);
+    scene
+        .draw_schedule
+        .add_stage(Stages::Draw, SystemStage::single_threaded())
+        .add_system_to_stage(Stages::Draw, draw::system);
 
-    fn update(&mut self, delta: f32) -> GameResult<()> {
-        let mut time = self.world.get_resource_mut::<Time>().unwrap();
-        time.0 = delta;
-        self.schedule.run(&mut self.world);
-        Ok(())
+    let world = &mut scene.world;
+    world
+        .spawn()
+        .insert_bundle(OverlayBundle::new(Color::new(0.0, 0.0, 0.0, 0.9)));
+    world.spawn().insert_bundle(TextBundle::new(
+        "Statistics".into(),
+        true,
+        vec2(WIDTH / 2.0, 60.0),
+        WHITE,
+        40,
+        assets.font,
+    ));
+
+    let stats = Statistics::load();
+    let seconds = stats.play_time as u32;
+    let mut rows = vec![
+        (
+            "Play Time".to_string(),
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+        ),
+        ("Longest Rally".into(), stats.longest_rally.to_string()),
+        ("Best Combo".into(), stats.best_combo.to_string()),
+        ("Highest Level".into(), stats.highest_level.to_string()),
+        (
+            "Power-ups Collected".into(),
+            stats.powerups_collected.to_string(),
+        ),
+    ];
+    for block_type in BlockType::ALL {
+        if let BlockType::Empty = block_type {
+            continue;
+        }
+        rows.push((
+            format!("{} Blocks Destroyed", block_type.name()),
+            stats.blocks_destroyed(block_type).to_string(),
+        ));
+    }
+    for ball_type in BallType::ALL {
+        rows.push((
+            format!("{} Balls Lost", ball_type.name()),
+            stats.balls_lost(ball_type).to_string(),
+        ));
+    }
+    for (i, (label, value)) in rows.into_iter().enumerate() {
+        let y = 110.0 + i as f32 * 32.0;
+        world.spawn().insert_bundle(TextBundle::new(
+            label,
+            false,
+            vec2(200.0, y),
+            WHITE,
+            24,
+            assets.font,
+        ));
+        world.spawn().insert_bundle(TextBundle::new(
+            value,
+            false,
+            vec2(520.0, y),
+            WHITE,
+            24,
+            assets.font,
+        ));
+    }
+    AddMenu {
+        position: vec2(WIDTH / 2.0, HEIGHT - 40.0),
+        overlay: false,
+        state: None,
+        items: vec![("Back".into(), MenuAction::Back, true)],
     }
+    .write(world);
+    scene
 }
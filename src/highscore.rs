@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::GameResult, storage};
+
+/// Layout version of the high-score file.
+pub const HIGHSCORE_VERSION: u32 = 1;
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    Campaign,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 1] = [GameMode::Campaign];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Campaign => "Campaign",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: i32,
+    pub level: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    pub mode: GameMode,
+    pub entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScores {
+    pub version: u32,
+    pub tables: Vec<HighScoreTable>,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        HighScores {
+            version: HIGHSCORE_VERSION,
+            tables: Vec::new(),
+        }
+    }
+}
+
+impl HighScores {
+    pub fn path() -> Option<PathBuf> {
+        storage::data_file("highscores.toml")
+    }
+
+    pub fn load() -> HighScores {
+        storage::load(HighScores::path(), |scores: &HighScores| {
+            scores.version == HIGHSCORE_VERSION
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> GameResult<()> {
+        storage::save(HighScores::path(), self)
+    }
+
+    pub fn entries(&self, mode: GameMode) -> &[HighScoreEntry] {
+        self.tables
+            .iter()
+            .find(|table| table.mode == mode)
+            .map(|table| table.entries.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn best(&self, mode: GameMode) -> i32 {
+        self.entries(mode).first().map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, mode: GameMode, score: i32) -> bool {
+        let entries = self.entries(mode);
+        score > 0
+            && (entries.len() < MAX_ENTRIES || entries.iter().any(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, mode: GameMode, entry: HighScoreEntry) {
+        let index = match self.tables.iter().position(|table| table.mode == mode) {
+            Some(index) => index,
+            None => {
+                self.tables.push(HighScoreTable {
+                    mode,
+                    entries: Vec::new(),
+                });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].entries;
+        let position = entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(entries.len());
+        entries.insert(position, entry);
+        entries.truncate(MAX_ENTRIES);
+    }
+}
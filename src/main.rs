@@ -1,57 +1,89 @@
-use game::World;
+use component::resource::{SoundHandles, TextureHandles};
+use game::{title_scene, World};
 use game::{HEIGHT, WIDTH};
 use macroquad::{miniquad::conf::Icon, prelude::*};
 use macroquad_canvas::Canvas2D;
-use teuria::builder::GameWorldBuilder;
+use settings::Settings;
+use teuria::{asset::AssetKind, builder::GameWorldBuilder, manifest::AssetManifest};
 
 mod component;
 mod game;
+mod highscore;
+mod save;
+mod settings;
+mod statistics;
+mod storage;
 mod system;
 mod teuria;
+mod theme;
 mod utils;
 
 fn config() -> Conf {
     let small = Image::from_file_with_format(include_bytes!("../res/icon16.png"), None);
     let medium = Image::from_file_with_format(include_bytes!("../res/icon32.png"), None);
     let big = Image::from_file_with_format(include_bytes!("../res/icon64.png"), None);
+    let settings = Settings::load();
 
     Conf {
         window_title: "Breaker".into(),
-        window_width: 1024,
-        window_height: 620,
+        window_width: settings.window_width,
+        window_height: settings.window_height,
         icon: Some(Icon {
             small: small.bytes.try_into().unwrap(),
             medium: medium.bytes.try_into().unwrap(),
             big: big.bytes.try_into().unwrap(),
         }),
         high_dpi: true,
-        fullscreen: false,
+        fullscreen: settings.fullscreen,
         window_resizable: true,
         ..Default::default()
     }
 }
 
+/// Every asset the game loads, reread by the dev build when it changes.
+const MANIFEST: &str = "res/assets.toml";
+/// Longest step the simulation takes in one frame, in seconds.
+const MAX_FRAME_TIME: f32 = 0.1;
+
 #[macroquad::main(config)]
 async fn main() -> anyhow::Result<()> {
     rand::srand(macroquad::miniquad::date::now() as u64);
-    let delta = get_frame_time();
-    let font = load_ttf_font("res/Rubik-Light.ttf").await?;
+    let theme = theme::activate(&Settings::load().theme);
+    let manifest = AssetManifest::load(MANIFEST).await?;
 
     let mut game_world = GameWorldBuilder::new(Canvas2D::new(WIDTH, HEIGHT))
-        .insert_font(font)
-        .add_audio("HitBlock".into(), "res/hitblock.ogg")
-        .await?
-        .add_texture("Player".into(), "res/player.png")
-        .await?
-        .add_texture("Blocks".into(), "res/blocks-Sheet.png")
+        .require(AssetKind::Texture, &TextureHandles::REQUIRED)
+        .require(AssetKind::Sound, &SoundHandles::REQUIRED)
+        .load_manifest(&manifest, theme.dir())
         .await?
-        .add_texture("Ball".into(), "res/ball.png")
-        .await?
-        .build();
+        .build(Box::new(title_scene));
+    // Scenes get to save their progress before the window closes.
+    game_world.on_scene_exit(statistics::persist);
+    prevent_quit();
     game_world.start()?;
+    #[cfg(feature = "dev")]
+    {
+        if let Some(path) = Settings::path() {
+            game_world.watch(path, settings::reload);
+        }
+        if let Some(dir) = theme.dir() {
+            game_world.watch(dir.join(theme::PALETTE_FILE), theme::reload_palette);
+        }
+        game_world.watch_manifest(MANIFEST.into());
+        game_world.on_asset_reload(game::reload_handles);
+    }
 
-    loop {
+    while !game_world.should_quit() {
+        if is_quit_requested() {
+            game_world.quit();
+            continue;
+        }
+        #[cfg(feature = "dev")]
+        game_world.reload_changed().await;
+        // Capped so a stalled frame cannot tunnel the ball through walls.
+        let delta = get_frame_time().min(MAX_FRAME_TIME);
         game_world.update(delta)?;
         next_frame().await;
     }
+    Ok(())
 }
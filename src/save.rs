@@ -0,0 +1,172 @@
+use std::{fs, path::PathBuf};
+
+use bevy_ecs::{query::Without, system::Command, world::World};
+use macroquad::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{
+        resource::{Level, Palette, RunStats, Score, SpriteLayout, TextureHandles},
+        trigger::{ChangeState, ClearBoard},
+        BallBundle, BallType, BlockBundle, BlockType, Lives, Position, Tags, Velocity,
+    },
+    game::{GameResult, States},
+    highscore::GameMode,
+    storage,
+};
+
+/// Layout version of the saved run.
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedBlock {
+    pub position: [f32; 2],
+    pub block_type: BlockType,
+    pub lives: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedBall {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub ball_type: BallType,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub mode: GameMode,
+    pub level: u32,
+    pub level_start_score: i32,
+    pub score: i32,
+    pub paddle_position: [f32; 2],
+    pub paddle_lives: i32,
+    pub stats: RunStats,
+    pub blocks: Vec<SavedBlock>,
+    pub balls: Vec<SavedBall>,
+}
+
+impl SaveGame {
+    pub fn path() -> Option<PathBuf> {
+        storage::data_file("save.toml")
+    }
+
+    pub fn exists() -> bool {
+        SaveGame::path().is_some_and(|path| path.exists())
+    }
+
+    pub fn load() -> Option<SaveGame> {
+        storage::load(SaveGame::path(), |save: &SaveGame| {
+            save.version == SAVE_VERSION
+        })
+    }
+
+    pub fn save(&self) -> GameResult<()> {
+        storage::save(SaveGame::path(), self)
+    }
+
+    pub fn delete() -> GameResult<()> {
+        if let Some(path) = SaveGame::path().filter(|path| path.exists()) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn capture(world: &mut World) -> SaveGame {
+        let level = world.resource::<Level>();
+        let (level, level_start_score) = (level.number, level.start_score);
+
+        let mut paddle = world.query_filtered::<(&Position, &Lives), Without<Tags>>();
+        let (paddle_position, paddle_lives) = paddle
+            .get_single(world)
+            .map_or(([0.0, 0.0], 0), |(pos, lives)| (pos.0.to_array(), lives.0));
+
+        let mut blocks = world.query::<(&Position, &Tags, &Lives)>();
+        let blocks = blocks
+            .iter(world)
+            .filter_map(|(pos, tags, lives)| match tags {
+                Tags::Block(block_type) => Some(SavedBlock {
+                    position: pos.0.to_array(),
+                    block_type: *block_type,
+                    lives: lives.0,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let mut balls = world.query::<(&Position, &Velocity, &Tags)>();
+        let balls = balls
+            .iter(world)
+            .filter_map(|(pos, vel, tags)| match tags {
+                Tags::Ball(ball_type) => Some(SavedBall {
+                    position: pos.0.to_array(),
+                    velocity: vel.0.to_array(),
+                    ball_type: *ball_type,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        SaveGame {
+            version: SAVE_VERSION,
+            mode: *world.resource::<GameMode>(),
+            level,
+            level_start_score,
+            score: world.resource::<Score>().0,
+            paddle_position,
+            paddle_lives,
+            stats: *world.resource::<RunStats>(),
+            blocks,
+            balls,
+        }
+    }
+
+    /// Rebuilds a run inside a freshly created gameplay world.
+    pub fn restore(self, world: &mut World) {
+        let textures = *world.resource::<TextureHandles>();
+        let (block_texture, ball_texture) = (textures.blocks, textures.ball);
+
+        ClearBoard.write(world);
+
+        let mut paddle = world.query_filtered::<(&mut Position, &mut Lives), Without<Tags>>();
+        if let Ok((mut pos, mut lives)) = paddle.get_single_mut(world) {
+            pos.0 = Vec2::from_array(self.paddle_position);
+            lives.0 = self.paddle_lives;
+        }
+
+        let palette = *world.resource::<Palette>();
+        let clips = world.resource::<SpriteLayout>().block_clips.clone();
+        for block in self.blocks {
+            world.spawn().insert_bundle(BlockBundle::new(
+                Vec2::from_array(block.position),
+                block.block_type,
+                block.lives,
+                palette.block(block.block_type, block.lives),
+                block_texture,
+                &clips,
+            ));
+        }
+        for ball in self.balls {
+            world
+                .spawn()
+                .insert_bundle(BallBundle::new(
+                    Vec2::from_array(ball.position),
+                    palette.ball(ball.ball_type),
+                    ball_texture,
+                    ball.ball_type,
+                ))
+                .insert(Velocity(Vec2::from_array(ball.velocity)));
+        }
+
+        world.insert_resource(self.mode);
+        world.insert_resource(Score(self.score));
+        world.insert_resource(Level {
+            number: self.level,
+            start_score: self.level_start_score,
+        });
+        world.insert_resource(self.stats);
+        // Fresh gameplay scenes start out reviving; leaving that state now keeps
+        // `block::init_system` from building a new board over the restored one.
+        ChangeState(States::Playing).write(world);
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use macroquad::prelude::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{game::GameResult, storage, teuria::viewport::ScaleMode, theme::DEFAULT_THEME};
+
+pub const RESOLUTIONS: [(i32, i32); 4] = [(800, 560), (1024, 620), (1280, 800), (1600, 1000)];
+pub const BAR_COLORS: [(&str, [u8; 3]); 4] = [
+    ("Gray", [80, 80, 80]),
+    ("Black", [0, 0, 0]),
+    ("Navy", [20, 24, 60]),
+    ("Wine", [60, 16, 30]),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControlScheme {
+    Arrows,
+    Wasd,
+}
+
+impl ControlScheme {
+    pub fn keys(&self) -> (KeyCode, KeyCode) {
+        match self {
+            ControlScheme::Arrows => (KeyCode::Left, KeyCode::Right),
+            ControlScheme::Wasd => (KeyCode::A, KeyCode::D),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlScheme::Arrows => "Arrows",
+            ControlScheme::Wasd => "WASD",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaletteKind {
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl PaletteKind {
+    pub const ALL: [PaletteKind; 5] = [
+        PaletteKind::Default,
+        PaletteKind::Deuteranopia,
+        PaletteKind::Protanopia,
+        PaletteKind::Tritanopia,
+        PaletteKind::HighContrast,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaletteKind::Default => "Default",
+            PaletteKind::Deuteranopia => "Deuteranopia",
+            PaletteKind::Protanopia => "Protanopia",
+            PaletteKind::Tritanopia => "Tritanopia",
+            PaletteKind::HighContrast => "High Contrast",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_width: i32,
+    pub window_height: i32,
+    pub fullscreen: bool,
+    pub scale_mode: ScaleMode,
+    /// Fills the space around the canvas when it does not cover the window.
+    pub bar_color: [u8; 3],
+    /// Shows `res/bars.png` behind the canvas instead of `bar_color`, if it exists.
+    pub bar_image: bool,
+    /// Name of a directory under `res/themes`, read at startup.
+    pub theme: String,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub muted: bool,
+    /// Turns off screen shake and hit-stop.
+    pub reduced_motion: bool,
+    pub ball_trails: bool,
+    pub palette: PaletteKind,
+    /// Draws a pattern over blocks so their type does not depend on color alone.
+    pub block_patterns: bool,
+    pub controls: ControlScheme,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            window_width: 1024,
+            window_height: 620,
+            fullscreen: false,
+            scale_mode: ScaleMode::Letterbox,
+            bar_color: BAR_COLORS[0].1,
+            bar_image: false,
+            theme: DEFAULT_THEME.into(),
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 0.7,
+            muted: false,
+            reduced_motion: false,
+            ball_trails: true,
+            palette: PaletteKind::Default,
+            block_patterns: false,
+            controls: ControlScheme::Arrows,
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> Option<PathBuf> {
+        storage::config_file("settings.toml")
+    }
+
+    /// Reads the settings file, falling back to defaults if it is missing or malformed.
+    pub fn load() -> Settings {
+        storage::load(Settings::path(), |_: &Settings| true).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> GameResult<()> {
+        storage::save(Settings::path(), self)
+    }
+}
+
+pub fn reload(world: &mut bevy_ecs::world::World) {
+    world.insert_resource(Settings::load());
+}
@@ -0,0 +1,101 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use macroquad::prelude::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{BallType, BlockType},
+    game::GameResult,
+    storage,
+};
+
+/// Layout version of the statistics file.
+pub const STATISTICS_VERSION: u32 = 1;
+
+/// Totals accumulated over every run, kept across sessions.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub version: u32,
+    pub powerups_collected: u32,
+    pub play_time: f32,
+    pub longest_rally: u32,
+    pub best_combo: u32,
+    /// Furthest level reached; the level select offers everything up to it.
+    pub highest_level: u32,
+    pub blocks_destroyed: BTreeMap<String, u32>,
+    pub balls_lost: BTreeMap<String, u32>,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Statistics {
+            version: STATISTICS_VERSION,
+            powerups_collected: 0,
+            play_time: 0.0,
+            longest_rally: 0,
+            best_combo: 0,
+            highest_level: 1,
+            blocks_destroyed: BTreeMap::new(),
+            balls_lost: BTreeMap::new(),
+        }
+    }
+}
+
+impl Statistics {
+    pub fn path() -> Option<PathBuf> {
+        storage::data_file("statistics.toml")
+    }
+
+    pub fn load() -> Statistics {
+        storage::load(Statistics::path(), |stats: &Statistics| {
+            stats.version == STATISTICS_VERSION
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> GameResult<()> {
+        storage::save(Statistics::path(), self)
+    }
+
+    pub fn blocks_destroyed(&self, block_type: BlockType) -> u32 {
+        self.blocks_destroyed
+            .get(block_type.name())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn balls_lost(&self, ball_type: BallType) -> u32 {
+        self.balls_lost.get(ball_type.name()).copied().unwrap_or(0)
+    }
+
+    pub fn record_block(&mut self, block_type: BlockType) {
+        *self
+            .blocks_destroyed
+            .entry(block_type.name().into())
+            .or_default() += 1;
+    }
+
+    pub fn record_ball_lost(&mut self, ball_type: BallType) {
+        *self.balls_lost.entry(ball_type.name().into()).or_default() += 1;
+    }
+
+    pub fn record_rally(&mut self, rally: u32) {
+        self.longest_rally = self.longest_rally.max(rally);
+    }
+
+    pub fn record_combo(&mut self, combo: u32) {
+        self.best_combo = self.best_combo.max(combo);
+    }
+
+    pub fn record_level(&mut self, level: u32) {
+        self.highest_level = self.highest_level.max(level);
+    }
+}
+
+/// Saves the world's statistics, if it has any.
+pub fn persist(world: &mut bevy_ecs::world::World) {
+    if let Some(Err(e)) = world.get_resource::<Statistics>().map(Statistics::save) {
+        warn!("Failed to save statistics: {}", e);
+    }
+}
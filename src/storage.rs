@@ -0,0 +1,56 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use macroquad::prelude::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::game::GameResult;
+
+const APP_DIR: &str = "breaker";
+
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR).join(name))
+}
+
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR).join(name))
+}
+
+/// Reads a TOML file. A file that cannot be parsed, or that `current` rejects (usually an
+/// older layout version), is moved to `<name>.bak` so the next save cannot overwrite it.
+pub fn load<T: DeserializeOwned>(path: Option<PathBuf>, current: impl Fn(&T) -> bool) -> Option<T> {
+    let path = path?;
+    let contents = fs::read_to_string(&path).ok()?;
+    match toml::from_str::<T>(&contents) {
+        Ok(value) if current(&value) => return Some(value),
+        Ok(_) => warn!("{} is from another version", path.display()),
+        Err(e) => warn!("Failed to read {}: {}", path.display(), e),
+    }
+    set_aside(&path);
+    None
+}
+
+fn set_aside(path: &Path) {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    match fs::rename(path, &backup) {
+        Ok(()) => warn!("Kept the old file as {}", Path::new(&backup).display()),
+        Err(e) => warn!("Failed to back up {}: {}", path.display(), e),
+    }
+}
+
+/// Writes a TOML file through a temporary file, so an interrupted save leaves the old one intact.
+pub fn save<T: Serialize>(path: Option<PathBuf>, value: &T) -> GameResult<()> {
+    let path = path.ok_or_else(|| anyhow!("no config or data directory available"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, toml::to_string(value)?)?;
+    fs::rename(&temp, &path)?;
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use bevy_ecs::system::{Query, Res};
+
+use crate::component::{resource::Time, Animation, TextureComponent};
+
+pub fn system(delta: Res<Time>, mut query: Query<(&mut Animation, &mut TextureComponent)>) {
+    for (mut animation, mut texture) in query.iter_mut() {
+        animation.advance(delta.0);
+        texture.source = animation.source();
+    }
+}
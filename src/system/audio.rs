@@ -0,0 +1,59 @@
+use bevy_ecs::system::{Res, ResMut};
+
+use crate::{
+    component::resource::{Level, SoundEvents, SoundHandles},
+    game::States,
+    settings::Settings,
+    teuria::audio::AudioManager,
+    utils::random,
+};
+
+/// Fraction of the full volume a sound effect may randomly drop to.
+pub const MIN_VOLUME_VARIATION: f32 = 0.8;
+/// Every this many levels the gameplay track switches to the boss track.
+pub const BOSS_LEVEL_INTERVAL: u32 = 5;
+
+pub fn system(mut audio: ResMut<AudioManager>, settings: Res<Settings>) {
+    if settings.is_changed() {
+        audio.master_volume = settings.master_volume;
+        audio.sfx_volume = settings.sfx_volume;
+        audio.music_volume = settings.music_volume;
+        audio.muted = settings.muted;
+    }
+}
+
+/// Each playback varies in volume here and in pitch in `AudioManager`.
+pub fn event_system(
+    mut audio: ResMut<AudioManager>,
+    mut events: ResMut<SoundEvents>,
+    sounds: Res<SoundHandles>,
+) {
+    for sample in events.0.drain(..).filter_map(|event| event.sample(&sounds)) {
+        audio.play_sfx(sample, random(MIN_VOLUME_VARIATION, 1.0));
+    }
+}
+
+pub fn title_music_system(mut audio: ResMut<AudioManager>, sounds: Res<SoundHandles>) {
+    audio.ducked = false;
+    audio.play_music(sounds.title_music);
+}
+
+pub fn gameplay_music_system(
+    mut audio: ResMut<AudioManager>,
+    state: Res<States>,
+    level: Res<Level>,
+    sounds: Res<SoundHandles>,
+) {
+    audio.ducked = false;
+    match *state {
+        States::NameEntry | States::GameOver => audio.play_music(sounds.game_over_music),
+        _ if level.number.is_multiple_of(BOSS_LEVEL_INTERVAL) => {
+            audio.play_music(sounds.boss_music)
+        }
+        _ => audio.play_music(sounds.gameplay_music),
+    }
+}
+
+pub fn duck_system(mut audio: ResMut<AudioManager>) {
+    audio.ducked = true;
+}
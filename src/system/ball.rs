@@ -6,11 +6,13 @@ use bevy_ecs::{
 
 use crate::{
     component::{
-        resource::{Score, Time},
+        resource::{RunStats, Score, SoundEvent, SoundEvents, Time},
         trigger::AddPlayer,
         Aabb, BallType, Position, Tags, Velocity,
     },
     game::{HEIGHT, WIDTH},
+    statistics::Statistics,
+    teuria::camera::CameraEffects,
 };
 
 pub const BALL_NORMAL_SPEED: f32 = 250.0;
@@ -27,6 +29,8 @@ pub fn system(
     mut command: Commands,
     delta: Res<Time>,
     mut score: ResMut<Score>,
+    (mut stats, mut lifetime): (ResMut<RunStats>, ResMut<Statistics>),
+    (mut events, mut camera): (ResMut<SoundEvents>, ResMut<CameraEffects>),
     mut query: Query<BallQuery, With<Velocity>>,
 ) {
     let score_speed = score.0 as f32 / 32.0;
@@ -40,19 +44,29 @@ pub fn system(
         pos.0.x += vel.0.x * delta.0 * (BALL_NORMAL_SPEED + score_speed);
         pos.0.y += vel.0.y * delta.0 * (BALL_NORMAL_SPEED + score_speed);
 
-        if pos.0.x < 0f32 {
+        if pos.0.x < 0f32 && vel.0.x < 0f32 {
             vel.0.x = 1f32;
+            events.0.push(SoundEvent::WallBounce);
         }
-        if pos.0.x > WIDTH - aabb.0.w {
+        if pos.0.x > WIDTH - aabb.0.w && vel.0.x > 0f32 {
             vel.0.x = -1f32;
+            events.0.push(SoundEvent::WallBounce);
         }
-        if pos.0.y < 0f32 {
+        if pos.0.y < 0f32 && vel.0.y < 0f32 {
             vel.0.y = 1f32;
+            events.0.push(SoundEvent::WallBounce);
         }
         if pos.0.y > HEIGHT + aabb.0.h {
             command.entity(e).despawn();
+            stats.balls_lost += 1;
+            if let Tags::Ball(ball_type) = tags {
+                lifetime.record_ball_lost(*ball_type);
+                events.0.push(SoundEvent::BallLost(*ball_type));
+            }
             match tags {
                 Tags::Ball(BallType::Original) => {
+                    stats.rally = 0;
+                    camera.add_trauma(0.6);
                     command.add(AddPlayer);
                 }
                 Tags::Ball(BallType::Clone) => {
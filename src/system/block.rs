@@ -1,27 +1,30 @@
-use std::collections::HashMap;
-
 use bevy_ecs::{
     prelude::Entity,
     system::{Commands, Query, Res, ResMut},
 };
-use macroquad::{
-    prelude::{vec2, Rect, BLACK, BLUE, GREEN, ORANGE, PINK, RED},
-    texture::Texture2D,
-};
+use macroquad::prelude::vec2;
 
 use crate::{
     component::{
-        resource::Score, trigger::AddBall, Aabb, BallBundle, BallType, BlockType, Bounty,
-        ColorComponent, Lives, Position, Tags, TextureComponent,
+        resource::{
+            Palette, RunStats, Score, SoundEvent, SoundEvents, SpriteLayout, TextureHandles,
+        },
+        trigger::{AddBall, ChangeState, EmitParticles},
+        tween::{Easing, Tween, TweenTarget},
+        Animation, BallBundle, BallType, BlockBundle, BlockType, Bounty, ColorComponent, Emitter,
+        Lives, Position, Scale, Tags,
     },
     game::{States, WIDTH},
+    statistics::Statistics,
+    teuria::camera::CameraEffects,
     utils::random,
 };
 
 pub fn init_system(
     mut command: Commands,
-    textures: Res<HashMap<String, Texture2D>>,
-    mut state: ResMut<States>,
+    textures: Res<TextureHandles>,
+    palette: Res<Palette>,
+    layout: Res<SpriteLayout>,
 ) {
     let (width, height) = (6, 6);
     let padding = 0f32;
@@ -30,40 +33,40 @@ pub fn init_system(
         (WIDTH - (total_block_size.x * width as f32)) * 0.5f32,
         50f32,
     );
-    let texture = textures
-        .get("Blocks")
-        .expect("No textures found!")
-        .to_owned();
+    let texture = textures.blocks;
     let length = width * height;
-    command.spawn_batch((0..length).map(move |i| {
+    for i in 0..length {
         let block_x = (i % width) as f32 * total_block_size.x;
         let block_y = (i / width) as f32 * total_block_size.y;
         let position = board_start_pos + vec2(block_x, block_y);
         let random = random(0, 100);
-        let (block_type, bounty, lives, color) = if random < 80 {
-            (BlockType::Fragile, 50, 3, RED)
+        let block_type = if random < 80 {
+            BlockType::Fragile
         } else if random < 90 {
-            (BlockType::Cloner, 30, 2, BLUE)
+            BlockType::Cloner
         } else if random < 95 {
-            (BlockType::Empty, 0, 0, BLACK)
+            BlockType::Empty
         } else {
-            (BlockType::Special, 100, 1, PINK)
+            BlockType::Special
         };
-
-        (
-            Position(position),
-            Aabb(Rect::new(0.0, 0.0, 100.0, 40.0)),
-            ColorComponent(color),
-            Tags::Block(block_type),
-            Bounty(bounty),
-            Lives(lives),
-            TextureComponent {
+        command
+            .spawn()
+            .insert_bundle(BlockBundle::new(
+                position,
+                block_type,
+                block_type.lives(),
+                palette.block(block_type, block_type.lives()),
                 texture,
-                source: Rect::new(0.0, 0.0, 100.0, 40.0),
-            },
-        )
-    }));
-    *state = States::Playing;
+                &layout.block_clips,
+            ))
+            .insert(Scale(0.0))
+            .insert(Tween::delay(i as f32 * 0.015).then(
+                0.3,
+                Easing::BackOut,
+                TweenTarget::Scale(0.0, 1.0),
+            ));
+    }
+    command.add(ChangeState(States::Playing));
 }
 
 pub fn system(
@@ -73,43 +76,77 @@ pub fn system(
         &Position,
         &Lives,
         &mut ColorComponent,
+        &mut Animation,
         &Bounty,
         &Tags,
     )>,
     mut score: ResMut<Score>,
-    textures: Res<HashMap<String, Texture2D>>,
+    (mut stats, mut lifetime): (ResMut<RunStats>, ResMut<Statistics>),
+    (mut events, mut camera): (ResMut<SoundEvents>, ResMut<CameraEffects>),
+    (textures, palette): (Res<TextureHandles>, Res<Palette>),
 ) {
-    for (e, pos, lives, mut color, bounty, tags) in query.iter_mut() {
+    for (e, pos, lives, mut color, mut animation, bounty, tags) in query.iter_mut() {
         if let Tags::Block(s) = tags {
+            // Broken blocks keep their last color for the debris.
+            if lives.0 > 0 {
+                color.0 = palette.block(*s, lives.0);
+            }
             match lives.0 {
-                3 => {}
-                2 => color.0 = BLUE,
-                1 => {
-                    if let BlockType::Special = s {
-                        continue;
-                    }
-                    color.0 = ORANGE;
-                }
+                // Cracks follow the damage taken, as block types start with different lives.
+                lives if lives > 0 => match s.lives() - lives {
+                    0 => {}
+                    1 => animation.play("cracked"),
+                    _ => animation.play("shattered"),
+                },
                 0 | -1 => {
                     match s {
                         BlockType::Cloner => {
-                            let textures = textures.get("Ball").unwrap();
                             let pos = vec2(pos.0.x + 75.0, pos.0.y + 20.0);
-                            let bundle = BallBundle::new(pos, BLUE, *textures, BallType::Clone);
+                            let bundle = BallBundle::new(
+                                pos,
+                                palette.ball(BallType::Clone),
+                                textures.ball,
+                                BallType::Clone,
+                            );
                             commands.add(AddBall(bundle));
+                            lifetime.powerups_collected += 1;
                         }
                         BlockType::Special => {
-                            let textures = textures.get("Ball").unwrap();
                             let pos = vec2(pos.0.x + 75.0, pos.0.y + 20.0);
-                            let bundle = BallBundle::new(pos, PINK, *textures, BallType::Special);
+                            let bundle = BallBundle::new(
+                                pos,
+                                palette.ball(BallType::Special),
+                                textures.ball,
+                                BallType::Special,
+                            );
                             commands.add(AddBall(bundle));
+                            lifetime.powerups_collected += 1;
                         }
                         _ => {}
                     }
                     score.0 += bounty.0;
+                    // Empty blocks clear themselves as soon as they spawn.
+                    if !matches!(s, BlockType::Empty) {
+                        stats.blocks_broken += 1;
+                        stats.combo += 1;
+                        lifetime.record_block(*s);
+                        lifetime.record_combo(stats.combo);
+                        events.0.push(SoundEvent::BlockDestroyed(*s));
+                        if let BlockType::Special = s {
+                            camera.add_trauma(0.5);
+                            camera.hit_stop(4);
+                        } else {
+                            camera.add_trauma(0.15);
+                        }
+                        commands.add(EmitParticles {
+                            emitter: Emitter::DEBRIS,
+                            position: pos.0 + vec2(50.0, 20.0),
+                            color: color.0,
+                        });
+                    }
                     commands.entity(e).despawn();
                 }
-                _ => color.0 = GREEN,
+                _ => {}
             }
         }
     }
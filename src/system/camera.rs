@@ -0,0 +1,9 @@
+use bevy_ecs::system::{Res, ResMut};
+
+use crate::{settings::Settings, teuria::camera::CameraEffects};
+
+pub fn system(mut effects: ResMut<CameraEffects>, settings: Res<Settings>) {
+    if settings.is_changed() {
+        effects.enabled = !settings.reduced_motion;
+    }
+}
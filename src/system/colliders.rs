@@ -1,15 +1,18 @@
-use std::collections::HashMap;
-
 use bevy_ecs::{
     prelude::Entity,
     query::{With, Without},
-    system::{Query, Res, ResMut},
+    system::{Commands, Query, Res, ResMut},
 };
-use macroquad::{prelude::Rect, audio::{Sound, play_sound_once}};
+use macroquad::prelude::{vec2, Rect, YELLOW};
 
 use crate::{
-    component::{resource::ColliderResource, Aabb, BallType, Lives, Position, Tags, Velocity},
+    component::{
+        resource::{ColliderResource, RunStats, SoundEvent, SoundEvents},
+        trigger::{ChangeState, EmitParticles},
+        Aabb, BallType, Emitter, Lives, Position, Tags, Velocity,
+    },
     game::States,
+    statistics::Statistics,
 };
 
 pub fn query_all_colliders(
@@ -23,15 +26,16 @@ pub fn query_all_colliders(
 }
 
 pub fn system(
+    mut command: Commands,
     mut query: Query<(&mut Position, &mut Velocity, &Aabb, &Tags)>,
     mut block_query: Query<&mut Lives, With<Tags>>,
     colliders: Res<ColliderResource>,
-    sound: Res<HashMap<String, Sound>>,
-    mut state: ResMut<States>,
+    mut events: ResMut<SoundEvents>,
+    (mut stats, mut lifetime): (ResMut<RunStats>, ResMut<Statistics>),
 ) {
     let colliders = &colliders.0;
     if colliders.len() <= 1 {
-        *state = States::Restarting;
+        command.add(ChangeState(States::Restarting));
     }
 
     for (mut pos, mut vel, aabb, tags) in query.iter_mut() {
@@ -49,14 +53,27 @@ pub fn system(
                 pos.0.x -= to_signum.x * intersection.w;
                 vel.0.x = -to_signum.x * vel.0.x.abs();
             }
-            if let Ok(mut lives) = block_query.get_component_mut::<Lives>(e) {
+            if let Ok(mut lives) = block_query.get_mut(e) {
                 if let Tags::Ball(BallType::Special) = tags {
                     lives.0 = 0;
                 } else {
                     lives.0 -= 1;
                 }
-                let sound = sound["HitBlock"];
-                play_sound_once(sound);
+                // Destruction is announced by `block::system` when it despawns the block.
+                if lives.0 > 0 {
+                    events.0.push(SoundEvent::BlockDamaged);
+                }
+            } else {
+                events.0.push(SoundEvent::PaddleHit);
+                command.add(EmitParticles {
+                    emitter: Emitter::SPARKS,
+                    position: vec2(rect.x + rect.w * 0.5, rect.y + rect.h),
+                    color: YELLOW,
+                });
+                // Everything a ball bounces off is tagged except the paddle.
+                stats.rally += 1;
+                stats.combo = 0;
+                lifetime.record_rally(stats.rally);
             }
         };
 
@@ -1,31 +1,169 @@
-use bevy_ecs::system::{Query, Res};
+use bevy_ecs::{
+    prelude::Entity,
+    system::{Query, Res},
+};
 use macroquad::{
-    prelude::{set_camera, set_default_camera, BLACK, DARKGRAY},
+    prelude::{set_camera, vec2, Color, Rect, WHITE},
+    shapes::draw_rectangle,
     texture::{draw_texture_ex, DrawTextureParams},
-    window::clear_background,
 };
 
-use crate::component::{ColorComponent, Position, Resolution, TextureComponent};
+use crate::{
+    component::{
+        resource::Palette, Aabb, Background, ColorComponent, Overlay, Particle, PatternKind,
+        Position, Scale, ScreenSpace, Tags, TextureComponent, Trail, ZIndex,
+    },
+    game::{HEIGHT, WIDTH},
+    teuria::{asset::AssetRegistry, camera::SceneCameras},
+};
+
+use super::{
+    palette, particle,
+    text::{self, TextQuery},
+    trail,
+};
 
 type DrawQuery<'a> = (&'a Position, &'a TextureComponent, &'a ColorComponent);
+type LayerQuery<'a> = (Entity, Option<&'a ZIndex>, Option<&'a ScreenSpace>);
+type OverlayQuery<'a> = (&'a Overlay, LayerQuery<'a>);
+type BackgroundQuery<'a> = (&'a Background, LayerQuery<'a>);
+type PatternQuery<'a> = (
+    &'a Position,
+    &'a Aabb,
+    &'a Tags,
+    Option<&'a Scale>,
+    LayerQuery<'a>,
+);
 
-pub fn system(query: Query<DrawQuery>) {
-    for (pos, tex, color) in query.iter() {
-        let texture_params = DrawTextureParams {
-            source: Some(tex.source),
-            ..Default::default()
-        };
-        draw_texture_ex(tex.texture, pos.0.x, pos.0.y, color.0, texture_params);
-    }
+enum Drawable<'a> {
+    Sprite(DrawQuery<'a>, f32),
+    Pattern(PatternKind, Rect, Color, f32),
+    Particle(&'a Position, &'a Particle),
+    Trail(&'a Trail, &'a ColorComponent),
+    Text(TextQuery<'a>, f32),
+    Overlay(&'a Overlay),
+    Background(&'a Background),
 }
 
-pub fn pre_system(resolution: Res<Resolution>) {
-    set_camera(&resolution.0.camera);
-    clear_background(BLACK);
-}
+/// Draws sprites, particles, text and overlays in one pass ordered by `ZIndex`.
+/// Ties are broken by `Entity`, so overlapping entities on one index do not swap between frames.
+pub fn system(
+    (cameras, registry): (Res<SceneCameras>, Res<AssetRegistry>),
+    sprites: Query<(DrawQuery, Option<&Scale>, LayerQuery)>,
+    particles: Query<(&Position, &Particle, LayerQuery)>,
+    trails: Query<(&Trail, &ColorComponent, LayerQuery)>,
+    texts: Query<(TextQuery, Option<&Scale>, LayerQuery)>,
+    (overlays, backgrounds): (Query<OverlayQuery>, Query<BackgroundQuery>),
+    (blocks, palette): (Query<PatternQuery>, Option<Res<Palette>>),
+) {
+    let layer = |(entity, z, screen): LayerQuery| {
+        (
+            z.copied().unwrap_or(ZIndex::BOARD),
+            entity,
+            screen.is_some(),
+        )
+    };
+    let scale = |scale: Option<&Scale>| scale.map_or(1.0, |scale| scale.0);
+    let mut drawables = Vec::new();
+    drawables.extend(
+        sprites
+            .iter()
+            .map(|(sprite, s, l)| (layer(l), Drawable::Sprite(sprite, scale(s)))),
+    );
+    // Patterns come right after the sprites so they land on top of their block.
+    if let Some(palette) = palette.filter(|palette| palette.patterns) {
+        drawables.extend(blocks.iter().filter_map(|(pos, aabb, tags, s, l)| {
+            let kind = match tags {
+                Tags::Block(block_type) => block_type.pattern()?,
+                _ => return None,
+            };
+            let scale = scale(s);
+            let size = aabb.0.size();
+            let origin = pos.0 + aabb.0.point() + size * (1.0 - scale) * 0.5;
+            let rect = Rect::new(origin.x, origin.y, size.x * scale, size.y * scale);
+            Some((
+                layer(l),
+                Drawable::Pattern(kind, rect, palette.pattern, scale),
+            ))
+        }));
+    }
+    drawables.extend(
+        particles
+            .iter()
+            .map(|(pos, p, l)| (layer(l), Drawable::Particle(pos, p))),
+    );
+    // Trails sit just below the ball that leaves them.
+    drawables.extend(trails.iter().map(|(trail, color, l)| {
+        let (z, entity, screen) = layer(l);
+        (
+            (ZIndex(z.0 - 1), entity, screen),
+            Drawable::Trail(trail, color),
+        )
+    }));
+    drawables.extend(
+        texts
+            .iter()
+            .map(|(t, s, l)| (layer(l), Drawable::Text(t, scale(s)))),
+    );
+    drawables.extend(
+        overlays
+            .iter()
+            .map(|(o, l)| (layer(l), Drawable::Overlay(o))),
+    );
+    drawables.extend(
+        backgrounds
+            .iter()
+            .map(|(b, l)| (layer(l), Drawable::Background(b))),
+    );
+    drawables.sort_by_key(|((z, entity, _), _)| (*z, *entity));
 
-pub fn post_system(resolution: Res<Resolution>) {
-    set_default_camera();
-    clear_background(DARKGRAY);
-    resolution.0.draw();
+    let mut on_screen = false;
+    for ((_, _, screen), drawable) in drawables {
+        if screen != on_screen {
+            on_screen = screen;
+            set_camera(if screen {
+                &cameras.screen
+            } else {
+                &cameras.world
+            });
+        }
+        match drawable {
+            Drawable::Sprite((pos, tex, color), scale) => {
+                let size = tex.source.size();
+                let texture_params = DrawTextureParams {
+                    source: Some(tex.source),
+                    dest_size: Some(size * scale),
+                    ..Default::default()
+                };
+                let pos = pos.0 + size * (1.0 - scale) * 0.5;
+                draw_texture_ex(
+                    registry.textures[tex.texture],
+                    pos.x,
+                    pos.y,
+                    color.0,
+                    texture_params,
+                );
+            }
+            Drawable::Pattern(kind, rect, color, scale) => palette::draw(kind, rect, color, scale),
+            Drawable::Particle(pos, p) => particle::draw(pos, p),
+            Drawable::Trail(t, color) => trail::draw(t, color.0),
+            Drawable::Text((txt, color, font, rect), scale) => {
+                text::draw(txt, color, font, rect, scale)
+            }
+            Drawable::Overlay(overlay) => draw_rectangle(0.0, 0.0, WIDTH, HEIGHT, overlay.0),
+            Drawable::Background(background) => draw_texture_ex(
+                registry.textures[background.0],
+                0.0,
+                0.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(WIDTH, HEIGHT)),
+                    ..Default::default()
+                },
+            ),
+        }
+    }
+    if on_screen {
+        set_camera(&cameras.world);
+    }
 }
@@ -0,0 +1,74 @@
+use bevy_ecs::system::{Commands, Query, Res, ResMut};
+use macroquad::prelude::{is_key_pressed, warn, KeyCode, WHITE, YELLOW};
+
+use crate::{
+    component::{
+        resource::{Level, NameEntry, Score},
+        trigger::ChangeState,
+        ColorComponent, NameLetter, Text,
+    },
+    game::States,
+    highscore::{GameMode, HighScoreEntry, HighScores},
+};
+
+type LetterQuery<'a> = (&'a NameLetter, &'a mut Text, &'a mut ColorComponent);
+
+pub fn name_entry_system(
+    mut command: Commands,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    level: Res<Level>,
+    mut entry: ResMut<NameEntry>,
+    mut highscores: ResMut<HighScores>,
+    mut query: Query<LetterQuery>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let cursor = entry.cursor;
+    if is_key_pressed(KeyCode::Up) {
+        entry.letters[cursor] = if entry.letters[cursor] == b'Z' {
+            b'A'
+        } else {
+            entry.letters[cursor] + 1
+        };
+    }
+    if is_key_pressed(KeyCode::Down) {
+        entry.letters[cursor] = if entry.letters[cursor] == b'A' {
+            b'Z'
+        } else {
+            entry.letters[cursor] - 1
+        };
+    }
+    if is_key_pressed(KeyCode::Right) {
+        entry.cursor = (cursor + 1) % 3;
+    }
+    if is_key_pressed(KeyCode::Left) {
+        entry.cursor = (cursor + 2) % 3;
+    }
+
+    for (letter, mut text, mut color) in query.iter_mut() {
+        text.text = (entry.letters[letter.0] as char).to_string();
+        color.0 = if letter.0 == entry.cursor {
+            YELLOW
+        } else {
+            WHITE
+        };
+    }
+
+    if is_key_pressed(KeyCode::Enter) {
+        highscores.insert(
+            *mode,
+            HighScoreEntry {
+                name: String::from_utf8_lossy(&entry.letters).into_owned(),
+                score: score.0,
+                level: level.number,
+            },
+        );
+        if let Err(e) = highscores.save() {
+            warn!("Failed to save high scores: {}", e);
+        }
+        command.add(ChangeState(States::GameOver));
+    }
+}
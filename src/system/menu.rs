@@ -0,0 +1,171 @@
+use bevy_ecs::{
+    system::{Command, Commands, Local, Query, Res, ResMut},
+    world::World,
+};
+use macroquad::{
+    prelude::{
+        is_key_pressed, is_mouse_button_pressed, warn, Color, KeyCode, MouseButton, Rect, Vec2,
+        DARKGRAY, WHITE, YELLOW,
+    },
+    text::measure_text,
+};
+
+use crate::{
+    component::{
+        resource::MenuSelection,
+        trigger::{AddPlayerLives, ChangeState, RestartCampaign, RestartLevel},
+        ColorComponent, FontComponent, MenuAction, MenuItem, Position, Text,
+    },
+    game::{
+        audio_options_scene, continue_scene, gameplay_options_scene, gameplay_scene,
+        highscores_scene, level_scene, level_select_scene, options_scene, statistics_scene,
+        title_scene, video_options_scene, States,
+    },
+    save::SaveGame,
+    teuria::{
+        scene::{SceneRequest, SceneTransition},
+        viewport::MousePosition,
+    },
+};
+
+pub type LabelQuery<'a> = (&'a Text, &'a FontComponent, &'a Position);
+
+/// Whether the mouse is over a label's text.
+pub fn hovered((text, font, position): LabelQuery, mouse: Vec2) -> bool {
+    let size = measure_text(&text.text, Some(font.font), font.size, 1.0);
+    let left = if text.center {
+        position.0.x - size.width * 0.5
+    } else {
+        position.0.x
+    };
+    Rect::new(left, position.0.y - size.offset_y, size.width, size.height).contains(mouse)
+}
+
+pub fn system(
+    mut command: Commands,
+    mut selection: ResMut<MenuSelection>,
+    mut request: ResMut<SceneRequest>,
+    (mouse, mut last_mouse): (Res<MousePosition>, Local<Vec2>),
+    mut query: Query<(&MenuItem, &mut ColorComponent, LabelQuery)>,
+) {
+    let mut enabled = query
+        .iter()
+        .filter(|(item, _, _)| item.enabled)
+        .map(|(item, _, _)| item.index)
+        .collect::<Vec<usize>>();
+    if enabled.is_empty() {
+        return;
+    }
+    enabled.sort_unstable();
+
+    let len = enabled.len();
+    let mut current = enabled
+        .iter()
+        .position(|index| *index == selection.0)
+        .unwrap_or(0);
+    if is_key_pressed(KeyCode::Down) {
+        current = (current + 1) % len;
+    }
+    if is_key_pressed(KeyCode::Up) {
+        current = (current + len - 1) % len;
+    }
+    selection.0 = enabled[current];
+
+    // A resting cursor must not fight the keyboard, so hovering only selects once the mouse moves.
+    let hover = query
+        .iter()
+        .find(|(item, _, label)| item.enabled && hovered(*label, mouse.0))
+        .map(|(item, _, _)| item.index);
+    let clicked = is_mouse_button_pressed(MouseButton::Left) && hover.is_some();
+    if let Some(index) = hover.filter(|_| clicked || mouse.0 != *last_mouse) {
+        selection.0 = index;
+    }
+    *last_mouse = mouse.0;
+
+    let mut action = None;
+    for (item, mut color, _) in query.iter_mut() {
+        let tint = if !item.enabled {
+            DARKGRAY
+        } else if item.index == selection.0 {
+            action = Some(item.action);
+            YELLOW
+        } else {
+            WHITE
+        };
+        // Keep the alpha so menu fade-ins are not overridden.
+        color.0 = Color {
+            a: color.0.a,
+            ..tint
+        };
+    }
+
+    if !is_key_pressed(KeyCode::Enter) && !clicked {
+        return;
+    }
+    match action {
+        Some(MenuAction::Play) => {
+            request.0 = Some(SceneTransition::Replace(Box::new(gameplay_scene)))
+        }
+        Some(MenuAction::LevelSelect) => {
+            request.0 = Some(SceneTransition::Push(Box::new(level_select_scene)))
+        }
+        Some(MenuAction::StartLevel(number)) => {
+            request.0 = Some(SceneTransition::Reset(level_scene(number)))
+        }
+        Some(MenuAction::Continue) => {
+            request.0 = Some(SceneTransition::Replace(Box::new(continue_scene)))
+        }
+        Some(MenuAction::Quit) => request.0 = Some(SceneTransition::Quit),
+        Some(MenuAction::Resume) => request.0 = Some(SceneTransition::Pop(None)),
+        Some(MenuAction::RestartLevel) => {
+            request.0 = Some(SceneTransition::Pop(Some(restart_level)))
+        }
+        Some(MenuAction::QuitToTitle) => {
+            request.0 = Some(SceneTransition::Reset(Box::new(title_scene)))
+        }
+        Some(MenuAction::SaveAndQuit) => {
+            request.0 = Some(SceneTransition::Pop(Some(save_and_quit)))
+        }
+        Some(MenuAction::Options) => {
+            request.0 = Some(SceneTransition::Push(Box::new(options_scene)))
+        }
+        Some(MenuAction::VideoOptions) => {
+            request.0 = Some(SceneTransition::Push(Box::new(video_options_scene)))
+        }
+        Some(MenuAction::AudioOptions) => {
+            request.0 = Some(SceneTransition::Push(Box::new(audio_options_scene)))
+        }
+        Some(MenuAction::GameplayOptions) => {
+            request.0 = Some(SceneTransition::Push(Box::new(gameplay_options_scene)))
+        }
+        Some(MenuAction::HighScores) => {
+            request.0 = Some(SceneTransition::Push(Box::new(highscores_scene)))
+        }
+        Some(MenuAction::Statistics) => {
+            request.0 = Some(SceneTransition::Push(Box::new(statistics_scene)))
+        }
+        Some(MenuAction::Back) => request.0 = Some(SceneTransition::Pop(None)),
+        Some(MenuAction::RetryLevel) => {
+            command.add(RestartLevel);
+            command.add(AddPlayerLives(3));
+            command.add(ChangeState(States::Reviving));
+        }
+        Some(MenuAction::RestartCampaign) => {
+            command.add(RestartCampaign);
+            command.add(ChangeState(States::Reviving));
+        }
+        _ => {}
+    }
+}
+
+fn restart_level(world: &mut World) {
+    RestartLevel.write(world);
+    ChangeState(States::Reviving).write(world);
+}
+
+fn save_and_quit(world: &mut World) {
+    if let Err(e) = SaveGame::capture(world).save() {
+        warn!("Failed to save run: {}", e);
+    }
+    world.resource_mut::<SceneRequest>().0 = Some(SceneTransition::Reset(Box::new(title_scene)));
+}
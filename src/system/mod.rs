@@ -1,7 +1,20 @@
+pub mod animation;
+pub mod audio;
 pub mod ball;
 pub mod block;
+pub mod camera;
 pub mod colliders;
 pub mod draw;
+pub mod highscore;
+pub mod menu;
+pub mod options;
+pub mod palette;
+pub mod particle;
 pub mod pause;
 pub mod player;
+pub mod state;
+pub mod stats;
 pub mod text;
+pub mod trail;
+pub mod tween;
+pub mod viewport;
@@ -0,0 +1,187 @@
+use bevy_ecs::system::{Query, Res, ResMut};
+use macroquad::prelude::{is_key_pressed, is_mouse_button_pressed, warn, KeyCode, MouseButton};
+
+use crate::{
+    component::{
+        resource::{MenuSelection, TextureHandles},
+        FontComponent, MenuAction, MenuItem, Position, Text,
+    },
+    settings::{ControlScheme, PaletteKind, Settings, BAR_COLORS, RESOLUTIONS},
+    teuria::viewport::{MousePosition, ScaleMode},
+    theme,
+};
+
+use super::menu;
+
+pub fn system(
+    selection: Res<MenuSelection>,
+    mut settings: ResMut<Settings>,
+    (mouse, textures): (Res<MousePosition>, Res<TextureHandles>),
+    mut query: Query<(&MenuItem, &mut Text, &FontComponent, &Position)>,
+) {
+    let step = if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::Enter) {
+        1
+    } else if is_key_pressed(KeyCode::Left) {
+        -1
+    } else {
+        0
+    };
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+
+    for (item, mut text, font, position) in query.iter_mut() {
+        // Clicking a setting steps it forward like Enter.
+        let step = match step {
+            0 if clicked && menu::hovered((&text, font, position), mouse.0) => 1,
+            step => step,
+        };
+        if step != 0
+            && item.index == selection.0
+            && adjust(&mut settings, item.action, step, textures.bars.is_some())
+        {
+            if let Err(e) = settings.save() {
+                warn!("Failed to save settings: {}", e);
+            }
+        }
+        if let Some(label) = label(&settings, item.action) {
+            text.text = label;
+        }
+    }
+}
+
+/// `bar_image` says whether the manifest has a bar image to offer.
+fn adjust(settings: &mut Settings, action: MenuAction, step: i32, bar_image: bool) -> bool {
+    match action {
+        MenuAction::ToggleFullscreen => settings.fullscreen = !settings.fullscreen,
+        MenuAction::CycleResolution => {
+            let current = RESOLUTIONS
+                .iter()
+                .position(|size| *size == (settings.window_width, settings.window_height))
+                .unwrap_or(0) as i32;
+            let len = RESOLUTIONS.len() as i32;
+            let (width, height) = RESOLUTIONS[(current + step).rem_euclid(len) as usize];
+            settings.window_width = width;
+            settings.window_height = height;
+        }
+        MenuAction::CycleScaleMode => {
+            let current = ScaleMode::ALL
+                .iter()
+                .position(|mode| *mode == settings.scale_mode)
+                .unwrap_or(0) as i32;
+            let len = ScaleMode::ALL.len() as i32;
+            settings.scale_mode = ScaleMode::ALL[(current + step).rem_euclid(len) as usize];
+        }
+        MenuAction::CycleBars => {
+            // The bar image, when there is one, comes after the preset colors.
+            let len = BAR_COLORS.len() as i32;
+            let choices = if bar_image { len + 1 } else { len };
+            let current = if settings.bar_image {
+                len
+            } else {
+                BAR_COLORS
+                    .iter()
+                    .position(|(_, color)| *color == settings.bar_color)
+                    .unwrap_or(0) as i32
+            };
+            let next = (current + step).rem_euclid(choices);
+            settings.bar_image = next == len;
+            if let Some((_, color)) = BAR_COLORS.get(next as usize) {
+                settings.bar_color = *color;
+            }
+        }
+        MenuAction::CycleTheme => {
+            let themes = theme::available();
+            let current = themes
+                .iter()
+                .position(|name| *name == settings.theme)
+                .unwrap_or(0) as i32;
+            let len = themes.len() as i32;
+            settings.theme = themes[(current + step).rem_euclid(len) as usize].clone();
+        }
+        MenuAction::AdjustMasterVolume => step_volume(&mut settings.master_volume, step),
+        MenuAction::AdjustSfxVolume => step_volume(&mut settings.sfx_volume, step),
+        MenuAction::AdjustMusicVolume => step_volume(&mut settings.music_volume, step),
+        MenuAction::ToggleMute => settings.muted = !settings.muted,
+        MenuAction::ToggleReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+        MenuAction::ToggleTrails => settings.ball_trails = !settings.ball_trails,
+        MenuAction::CyclePalette => {
+            let current = PaletteKind::ALL
+                .iter()
+                .position(|kind| *kind == settings.palette)
+                .unwrap_or(0) as i32;
+            let len = PaletteKind::ALL.len() as i32;
+            settings.palette = PaletteKind::ALL[(current + step).rem_euclid(len) as usize];
+        }
+        MenuAction::TogglePatterns => settings.block_patterns = !settings.block_patterns,
+        MenuAction::CycleControls => {
+            settings.controls = match settings.controls {
+                ControlScheme::Arrows => ControlScheme::Wasd,
+                ControlScheme::Wasd => ControlScheme::Arrows,
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn step_volume(volume: &mut f32, step: i32) {
+    *volume = (*volume + step as f32 * 0.1).clamp(0.0, 1.0);
+}
+
+fn label(settings: &Settings, action: MenuAction) -> Option<String> {
+    let label = match action {
+        MenuAction::ToggleFullscreen => {
+            let mode = if settings.fullscreen {
+                "Fullscreen"
+            } else {
+                "Windowed"
+            };
+            format!("Window: {mode}")
+        }
+        MenuAction::CycleResolution => {
+            format!(
+                "Resolution: {}x{}",
+                settings.window_width, settings.window_height
+            )
+        }
+        MenuAction::CycleScaleMode => format!("Scaling: {}", settings.scale_mode.name()),
+        MenuAction::CycleBars => {
+            let name = if settings.bar_image {
+                "Image"
+            } else {
+                BAR_COLORS
+                    .iter()
+                    .find(|(_, color)| *color == settings.bar_color)
+                    .map_or("Custom", |(name, _)| name)
+            };
+            format!("Bars: {name}")
+        }
+        MenuAction::CycleTheme => format!("Theme: {}", settings.theme),
+        MenuAction::AdjustMasterVolume => {
+            format!("Master Volume: {:.0}%", settings.master_volume * 100.0)
+        }
+        MenuAction::AdjustSfxVolume => format!("SFX Volume: {:.0}%", settings.sfx_volume * 100.0),
+        MenuAction::AdjustMusicVolume => {
+            format!("Music Volume: {:.0}%", settings.music_volume * 100.0)
+        }
+        MenuAction::ToggleMute => {
+            let state = if settings.muted { "Off" } else { "On" };
+            format!("Sound: {state}")
+        }
+        MenuAction::ToggleReducedMotion => {
+            let state = if settings.reduced_motion { "On" } else { "Off" };
+            format!("Reduced Motion: {state}")
+        }
+        MenuAction::ToggleTrails => {
+            let state = if settings.ball_trails { "On" } else { "Off" };
+            format!("Ball Trails: {state}")
+        }
+        MenuAction::CyclePalette => format!("Palette: {}", settings.palette.name()),
+        MenuAction::TogglePatterns => {
+            let state = if settings.block_patterns { "On" } else { "Off" };
+            format!("Block Patterns: {state}")
+        }
+        MenuAction::CycleControls => format!("Controls: {}", settings.controls.name()),
+        _ => return None,
+    };
+    Some(label)
+}
@@ -0,0 +1,63 @@
+use bevy_ecs::system::{Query, Res, ResMut};
+use macroquad::{
+    prelude::{Color, Rect},
+    shapes::{draw_circle, draw_line},
+};
+
+use crate::{
+    component::{resource::Palette, ColorComponent, Lives, PatternKind, Tags},
+    settings::Settings,
+};
+
+/// Gap between pattern lines and dots at full scale.
+pub const PATTERN_SPACING: f32 = 12.0;
+
+/// Swaps the palette when the settings change and recolors the board with it.
+pub fn system(
+    settings: Res<Settings>,
+    mut palette: ResMut<Palette>,
+    mut query: Query<(&mut ColorComponent, Option<&Tags>, Option<&Lives>)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    *palette = Palette::new(settings.palette, settings.block_patterns);
+    for (mut color, tags, lives) in query.iter_mut() {
+        color.0 = match (tags, lives) {
+            (Some(Tags::Block(block_type)), Some(lives)) if lives.0 > 0 => {
+                palette.block(*block_type, lives.0)
+            }
+            (Some(Tags::Ball(ball_type)), _) => palette.ball(*ball_type),
+            // The paddle is the only untagged entity with lives.
+            (None, Some(_)) => palette.player,
+            _ => continue,
+        };
+    }
+}
+
+pub fn draw(kind: PatternKind, rect: Rect, color: Color, scale: f32) {
+    let spacing = PATTERN_SPACING * scale;
+    if spacing <= 0.0 {
+        return;
+    }
+    match kind {
+        PatternKind::Stripes => {
+            let mut x = rect.x + spacing * 0.5;
+            while x < rect.right() {
+                draw_line(x, rect.y, x, rect.bottom(), 2.0 * scale, color);
+                x += spacing;
+            }
+        }
+        PatternKind::Dots => {
+            let mut y = rect.y + spacing * 0.5;
+            while y < rect.bottom() {
+                let mut x = rect.x + spacing * 0.5;
+                while x < rect.right() {
+                    draw_circle(x, y, 2.5 * scale, color);
+                    x += spacing;
+                }
+                y += spacing;
+            }
+        }
+    }
+}
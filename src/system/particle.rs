@@ -0,0 +1,37 @@
+use bevy_ecs::{
+    prelude::Entity,
+    system::{Commands, Query, Res},
+};
+use macroquad::shapes::draw_rectangle;
+
+use crate::component::{resource::Time, Particle, Position};
+
+pub fn system(
+    mut commands: Commands,
+    delta: Res<Time>,
+    mut query: Query<(Entity, &mut Position, &mut Particle)>,
+) {
+    for (e, mut pos, mut particle) in query.iter_mut() {
+        particle.age += delta.0;
+        if particle.age >= particle.lifetime {
+            commands.entity(e).despawn();
+            continue;
+        }
+        particle.velocity.y += particle.gravity * delta.0;
+        pos.0 += particle.velocity * delta.0;
+    }
+}
+
+pub fn draw(pos: &Position, particle: &Particle) {
+    let t = (particle.age / particle.lifetime).clamp(0.0, 1.0);
+    let size = particle.size.0 + (particle.size.1 - particle.size.0) * t;
+    let mut color = particle.color;
+    color.a *= 1.0 - t;
+    draw_rectangle(
+        pos.0.x - size * 0.5,
+        pos.0.y - size * 0.5,
+        size,
+        size,
+        color,
+    );
+}
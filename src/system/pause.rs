@@ -1,15 +1,27 @@
-use bevy_ecs::system::ResMut;
+use bevy_ecs::system::{Res, ResMut};
 use macroquad::prelude::{is_key_pressed, KeyCode};
 
-use crate::game::States;
+use crate::{
+    game::{pause_scene, States},
+    teuria::scene::{FrameTime, SceneRequest, SceneTransition},
+};
 
-pub fn system(mut state: ResMut<States>) {
-    if !is_key_pressed(KeyCode::Escape) {
-        return;
+// macroquad 0.3 cannot tell us about focus loss or minimizing: it keeps minimize
+// events to itself (acting on them only on Android) and no backend reports focus.
+// A stalled frame is the closest stand-in. It catches platforms that stop drawing
+// hidden windows, but not an unfocused window that keeps rendering, and it also
+// fires on other hitches. Dev-build asset reloads are left out of `FrameTime`.
+pub const STALLED_FRAME_TIME: f32 = 0.25;
+
+pub fn system(mut request: ResMut<SceneRequest>, state: Res<States>, frame_time: Res<FrameTime>) {
+    let stalled = frame_time.0 > STALLED_FRAME_TIME;
+    if *state == States::Playing && (is_key_pressed(KeyCode::Escape) || stalled) {
+        request.0 = Some(SceneTransition::Push(Box::new(pause_scene)));
     }
-    *state = if *state == States::Paused {
-        States::Playing
-    } else {
-        States::Paused
+}
+
+pub fn resume_system(mut request: ResMut<SceneRequest>) {
+    if is_key_pressed(KeyCode::Escape) {
+        request.0 = Some(SceneTransition::Pop(None));
     }
 }
@@ -1,16 +1,18 @@
 use bevy_ecs::{
     query::Without,
-    system::{Commands, Query, Res, ResMut},
+    system::{Commands, Query, Res},
 };
 use macroquad::prelude::{is_key_down, KeyCode};
 
 use crate::{
     component::{
-        resource::{FontResource, Time},
-        trigger::AddGameOverText,
+        resource::{Score, Time},
+        trigger::ChangeState,
         Aabb, Lives, Position, Tags,
     },
     game::{States, WIDTH},
+    highscore::{GameMode, HighScores},
+    settings::Settings,
 };
 
 pub const PLAYER_SPEED: f32 = 900.0;
@@ -20,12 +22,15 @@ type PlayerQuery<'a> = (&'a mut Position, &'a Aabb, &'a Lives);
 pub fn system(
     mut command: Commands,
     delta: Res<Time>,
-    font: Res<FontResource>,
-    mut state: ResMut<States>,
+    settings: Res<Settings>,
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    highscores: Res<HighScores>,
     mut query: Query<PlayerQuery, Without<Tags>>,
 ) {
+    let (left, right) = settings.controls.keys();
     for (mut pos, aabb, lives) in query.iter_mut() {
-        let axis = get_axis(KeyCode::Left, KeyCode::Right);
+        let axis = get_axis(left, right);
 
         pos.0.x += axis * delta.0 * PLAYER_SPEED;
 
@@ -36,8 +41,12 @@ pub fn system(
             pos.0.x = WIDTH - aabb.0.w;
         }
         if lives.0 <= 0 {
-            *state = States::GameOver;
-            command.add(AddGameOverText(font.0));
+            let next = if highscores.qualifies(*mode, score.0) {
+                States::NameEntry
+            } else {
+                States::GameOver
+            };
+            command.add(ChangeState(next));
         }
     }
 }
@@ -0,0 +1,116 @@
+use bevy_ecs::{prelude::Entity, system::Command, world::World};
+use macroquad::prelude::{vec2, Vec2, WHITE};
+
+use crate::{
+    component::{
+        resource::{
+            FontResource, Level, Palette, Score, SoundEvent, SoundEvents, SpriteLayout,
+            TextureHandles,
+        },
+        trigger::{AddGameOverSummary, AddNameEntry},
+        tween::{self, Easing, Tween, TweenTarget},
+        BallBundle, BallType, LivesTag, PlayerBundle, Scale, StateTag, TextBundle, TextScoreBundle,
+    },
+    game::{States, HEIGHT, WIDTH},
+    statistics::{self, Statistics},
+    system::text,
+};
+
+pub fn enter(world: &mut World, state: States) {
+    match state {
+        States::Restarting => {
+            let score = world.resource::<Score>().0;
+            let mut level = world.resource_mut::<Level>();
+            level.number += 1;
+            level.start_score = score;
+            let level_number = level.number;
+            world
+                .resource_mut::<Statistics>()
+                .record_level(level_number);
+            statistics::persist(world);
+            world
+                .resource_mut::<SoundEvents>()
+                .0
+                .push(SoundEvent::LevelClear);
+            let font = world.resource::<FontResource>().0;
+            world
+                .spawn()
+                .insert_bundle(TextBundle::new(
+                    format!("Level {}", level_number),
+                    true,
+                    vec2(WIDTH / 2.0, HEIGHT / 2.0),
+                    WHITE,
+                    60,
+                    font,
+                ))
+                .insert(Scale(0.0))
+                .insert(
+                    Tween::new(0.6, Easing::ElasticOut, TweenTarget::Scale(0.0, 1.0))
+                        .then(0.8, Easing::Linear, TweenTarget::Scale(1.0, 1.0))
+                        .then(0.4, Easing::QuadInOut, TweenTarget::Alpha(1.0, 0.0))
+                        .on_complete(tween::despawn),
+                );
+        }
+        States::Reviving => {
+            let texture = world.resource::<TextureHandles>().ball;
+            let color = world.resource::<Palette>().ball(BallType::Original);
+            world.spawn().insert_bundle(BallBundle::new(
+                Vec2::new(180.0, 480.0),
+                color,
+                texture,
+                BallType::Original,
+            ));
+        }
+        States::NameEntry => AddNameEntry.write(world),
+        States::GameOver => {
+            statistics::persist(world);
+            AddGameOverSummary.write(world);
+        }
+        _ => {}
+    }
+}
+
+pub fn exit(world: &mut World, state: States) {
+    let mut query = world.query::<(Entity, &StateTag)>();
+    let scoped = query
+        .iter(world)
+        .filter(|(_, tag)| tag.0 == state)
+        .map(|(e, _)| e)
+        .collect::<Vec<Entity>>();
+    for e in scoped {
+        world.despawn(e);
+    }
+}
+
+pub fn spawn_player(world: &mut World) {
+    let font = world.resource::<FontResource>().0;
+    let texture = world.resource::<TextureHandles>().player;
+    let source = world.resource::<SpriteLayout>().paddle;
+    let color = world.resource::<Palette>().player;
+
+    world.spawn().insert_bundle(TextScoreBundle::new(
+        "Score".into(),
+        false,
+        vec2(25.0, 40.0),
+        WHITE,
+        text::SCORE_TEXT_SIZE,
+        font,
+    ));
+    world
+        .spawn()
+        .insert_bundle(TextBundle::new(
+            "Lives".into(),
+            false,
+            vec2(WIDTH / 1.2, 40.0),
+            WHITE,
+            30u16,
+            font,
+        ))
+        .insert(LivesTag);
+    world.spawn().insert_bundle(PlayerBundle::new(
+        Vec2::new(120.0, 500.0),
+        color,
+        texture,
+        source,
+    ));
+}
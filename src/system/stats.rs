@@ -0,0 +1,15 @@
+use bevy_ecs::system::{Res, ResMut};
+
+use crate::{
+    component::resource::{RunStats, Time},
+    statistics::Statistics,
+};
+
+pub fn time_system(
+    delta: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    mut lifetime: ResMut<Statistics>,
+) {
+    stats.time_played += delta.0;
+    lifetime.play_time += delta.0;
+}
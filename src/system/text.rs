@@ -1,22 +1,21 @@
 use bevy_ecs::{
     prelude::Entity,
     query::{With, Without},
-    system::{Commands, Query, Res, ResMut},
-};
-use macroquad::{
-    prelude::{is_key_pressed, KeyCode},
-    text::{draw_text_ex, measure_text, TextParams},
+    system::{Commands, Query, Res},
 };
+use macroquad::text::{draw_text_ex, measure_text, TextParams};
 
-use crate::{
-    component::{
-        resource::Score, trigger::AddPlayerLives, ColorComponent, FontComponent, GameOverTag,
-        Lives, LivesTag, Position, ScoreTag, Tags, Text,
-    },
-    game::States,
+use crate::component::{
+    resource::Score,
+    tween::{Easing, Tween, TweenTarget},
+    ColorComponent, FontComponent, Lives, LivesTag, Position, ScoreTag, Tags, Text,
 };
 
-type TextQuery<'a> = (
+pub const SCORE_TEXT_SIZE: u16 = 30;
+/// Size the score text briefly grows to whenever the score changes.
+pub const SCORE_PULSE_SIZE: u16 = 36;
+
+pub type TextQuery<'a> = (
     &'a Text,
     &'a ColorComponent,
     &'a FontComponent,
@@ -25,53 +24,56 @@ type TextQuery<'a> = (
 
 type MutableTextQuery<'a> = &'a mut Text;
 
-pub fn system(query: Query<TextQuery>) {
-    for (text, color, font, rect) in query.iter() {
-        let offset = if text.center {
-            measure_text(&text.text, Some(font.font), font.size, 1.0).width
-        } else {
-            0.0
-        };
-        draw_text_ex(
-            &text.text,
-            rect.0.x - offset,
-            rect.0.y,
-            TextParams {
-                font: font.font,
-                font_size: font.size,
-                color: color.0,
-                ..Default::default()
-            },
-        )
-    }
-}
-
-pub fn gameover_system(
-    mut command: Commands,
-    mut query: Query<(Entity, MutableTextQuery), With<GameOverTag>>,
-    ball_blocks_query: Query<Entity, With<Tags>>,
-    mut state: ResMut<States>,
-    mut score: ResMut<Score>,
+pub fn draw(
+    text: &Text,
+    color: &ColorComponent,
+    font: &FontComponent,
+    rect: &Position,
+    scale: f32,
 ) {
-    for (entity, mut text) in query.iter_mut() {
-        text.text = "Game Over!".into();
-        if is_key_pressed(KeyCode::Space) {
-            command.entity(entity).despawn();
-            for e in ball_blocks_query.iter() {
-                command.entity(e).despawn();
-                command.add(AddPlayerLives(3));
-            }
-            score.0 = 0;
-            *state = States::Reviving;
-        }
-    }
+    let offset = if text.center {
+        measure_text(&text.text, Some(font.font), font.size, scale).width * 0.5
+    } else {
+        0.0
+    };
+    draw_text_ex(
+        &text.text,
+        rect.0.x - offset,
+        rect.0.y,
+        TextParams {
+            font: font.font,
+            font_size: font.size,
+            font_scale: scale,
+            color: color.0,
+            ..Default::default()
+        },
+    )
 }
 
-pub fn score_system(score: Res<Score>, mut query: Query<MutableTextQuery, With<ScoreTag>>) {
-    for mut text in query.iter_mut() {
-        let score = score.0;
-        let tex = format!("Score: {score}");
+pub fn score_system(
+    mut commands: Commands,
+    score: Res<Score>,
+    mut query: Query<(Entity, MutableTextQuery), With<ScoreTag>>,
+) {
+    for (e, mut text) in query.iter_mut() {
+        let value = score.0;
+        let tex = format!("Score: {value}");
         text.text = tex;
+        if score.is_changed() {
+            let size = SCORE_TEXT_SIZE;
+            commands.entity(e).insert(
+                Tween::new(
+                    0.08,
+                    Easing::QuadOut,
+                    TweenTarget::TextSize(size, SCORE_PULSE_SIZE),
+                )
+                .then(
+                    0.15,
+                    Easing::QuadIn,
+                    TweenTarget::TextSize(SCORE_PULSE_SIZE, size),
+                ),
+            );
+        }
     }
 }
 
@@ -0,0 +1,58 @@
+use bevy_ecs::system::{Query, Res};
+use macroquad::{
+    prelude::{Color, Vec2},
+    shapes::{draw_circle, draw_line},
+};
+
+use crate::{
+    component::{resource::Time, Aabb, Position, Trail, TrailKind},
+    settings::Settings,
+};
+
+pub fn system(
+    delta: Res<Time>,
+    settings: Res<Settings>,
+    mut query: Query<(&Position, &Aabb, &mut Trail)>,
+) {
+    for (pos, aabb, mut trail) in query.iter_mut() {
+        if !settings.ball_trails {
+            trail.points.clear();
+            continue;
+        }
+        let duration = trail.style.duration;
+        for (_, age) in trail.points.iter_mut() {
+            *age += delta.0;
+        }
+        trail.points.retain(|(_, age)| *age < duration);
+        let center = pos.0 + aabb.0.point() + aabb.0.size() * 0.5;
+        trail.points.push_front((center, 0.0));
+    }
+}
+
+pub fn draw(trail: &Trail, color: Color) {
+    let fade = |age: f32| 1.0 - age / trail.style.duration;
+    let tint = |age: f32| Color {
+        a: color.a * fade(age) * 0.6,
+        ..color
+    };
+    match trail.style.kind {
+        TrailKind::Ribbon => {
+            let points = trail.points.iter().collect::<Vec<&(Vec2, f32)>>();
+            for pair in points.windows(2) {
+                let ((a, age), (b, _)) = (pair[0], pair[1]);
+                let width = trail.style.width * fade(*age);
+                draw_line(a.x, a.y, b.x, b.y, width, tint(*age));
+            }
+        }
+        TrailKind::Dots => {
+            for (point, age) in trail.points.iter().skip(1) {
+                draw_circle(
+                    point.x,
+                    point.y,
+                    trail.style.width * 0.5 * fade(*age),
+                    tint(*age),
+                );
+            }
+        }
+    }
+}
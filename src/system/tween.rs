@@ -0,0 +1,55 @@
+use bevy_ecs::{
+    prelude::Entity,
+    system::{Commands, Query, Res},
+};
+
+use crate::component::{
+    resource::Time,
+    trigger::CompleteTween,
+    tween::{Tween, TweenTarget},
+    ColorComponent, FontComponent, Position, Scale,
+};
+
+type TweenQuery<'a> = (
+    Entity,
+    &'a mut Tween,
+    Option<&'a mut Position>,
+    Option<&'a mut ColorComponent>,
+    Option<&'a mut Scale>,
+    Option<&'a mut FontComponent>,
+);
+
+pub fn system(mut commands: Commands, delta: Res<Time>, mut query: Query<TweenQuery>) {
+    for (e, mut tween, mut pos, mut color, mut scale, mut font) in query.iter_mut() {
+        for (target, t) in tween.advance(delta.0) {
+            match target {
+                TweenTarget::Position(from, to) => {
+                    if let Some(pos) = pos.as_mut() {
+                        pos.0 = from.lerp(to, t);
+                    }
+                }
+                TweenTarget::Alpha(from, to) => {
+                    if let Some(color) = color.as_mut() {
+                        color.0.a = from + (to - from) * t;
+                    }
+                }
+                TweenTarget::Scale(from, to) => {
+                    if let Some(scale) = scale.as_mut() {
+                        scale.0 = from + (to - from) * t;
+                    }
+                }
+                TweenTarget::TextSize(from, to) => {
+                    if let Some(font) = font.as_mut() {
+                        font.size = (from as f32 + (to as f32 - from as f32) * t).round() as u16;
+                    }
+                }
+            }
+        }
+        if tween.finished() {
+            commands.entity(e).remove::<Tween>();
+            if let Some(callback) = tween.callback() {
+                commands.add(CompleteTween(e, callback));
+            }
+        }
+    }
+}
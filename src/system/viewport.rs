@@ -0,0 +1,24 @@
+use bevy_ecs::system::{Res, ResMut};
+use macroquad::prelude::Color;
+
+use crate::{
+    component::resource::TextureHandles,
+    settings::Settings,
+    teuria::{asset::AssetRegistry, viewport::Viewport},
+};
+
+pub fn system(
+    mut viewport: ResMut<Viewport>,
+    settings: Res<Settings>,
+    (registry, textures): (Res<AssetRegistry>, Res<TextureHandles>),
+) {
+    if settings.is_changed() {
+        let [r, g, b] = settings.bar_color;
+        viewport.mode = settings.scale_mode;
+        viewport.bar_color = Color::from_rgba(r, g, b, 255);
+        viewport.bar_image = textures
+            .bars
+            .filter(|_| settings.bar_image)
+            .map(|bars| registry.textures[bars]);
+    }
+}
@@ -0,0 +1,631 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use macroquad::{
+    file::load_file,
+    prelude::{Image, Rect},
+    texture::{load_texture, Texture2D},
+};
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+const CHUNK_SLICE: u16 = 0x2022;
+
+#[derive(Debug)]
+pub struct AsepriteError(pub String);
+
+impl Display for AsepriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid aseprite file: {}", self.0)
+    }
+}
+
+impl std::error::Error for AsepriteError {}
+
+#[derive(Clone, Copy)]
+enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+/// A tagged range of frames, already unrolled into playback order.
+#[derive(Clone)]
+pub struct FrameTag {
+    pub name: String,
+    pub frames: Vec<usize>,
+}
+
+/// Frame layout of a sheet built from an Aseprite file, kept next to its texture.
+#[derive(Clone, Default)]
+pub struct SpriteSheet {
+    /// Source rect and duration in seconds of every frame, left to right.
+    pub frames: Vec<(Rect, f32)>,
+    pub tags: Vec<FrameTag>,
+    /// Bounds of each slice on the first frame, relative to the sheet.
+    pub slices: HashMap<String, Rect>,
+}
+
+struct Layer {
+    visible: bool,
+    opacity: u8,
+    /// Groups only contribute their visibility and opacity to their children.
+    group: bool,
+}
+
+enum CelData {
+    Pixels {
+        width: usize,
+        height: usize,
+        pixels: Vec<u8>,
+    },
+    Linked(usize),
+}
+
+struct Cel {
+    layer: usize,
+    x: i32,
+    y: i32,
+    opacity: u8,
+    data: CelData,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], AsepriteError> {
+        let end = self.position + count;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| AsepriteError("unexpected end of file".into()))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, AsepriteError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn word(&mut self) -> Result<u16, AsepriteError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn short(&mut self) -> Result<i16, AsepriteError> {
+        Ok(self.word()? as i16)
+    }
+
+    fn dword(&mut self) -> Result<u32, AsepriteError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn long(&mut self) -> Result<i32, AsepriteError> {
+        Ok(self.dword()? as i32)
+    }
+
+    fn string(&mut self) -> Result<String, AsepriteError> {
+        let length = self.word()? as usize;
+        Ok(String::from_utf8_lossy(self.take(length)?).into_owned())
+    }
+}
+
+/// Loads a texture, reading `.aseprite` sources directly so they need no exported PNG.
+/// Only Aseprite files come with a sheet layout.
+pub async fn load_sheet(path: &str) -> Result<(Texture2D, Option<SpriteSheet>), Box<dyn Error>> {
+    if !path.ends_with(".aseprite") {
+        return Ok((load_texture(path).await?, None));
+    }
+    let (image, sheet) = load(&load_file(path).await?)?;
+    Ok((Texture2D::from_image(&image), Some(sheet)))
+}
+
+/// Reads an `.aseprite` file, flattening its visible layers into a sheet with
+/// the frames laid out left to right.
+pub fn load(bytes: &[u8]) -> Result<(Image, SpriteSheet), AsepriteError> {
+    let mut file = Reader { bytes, position: 0 };
+    file.dword()?;
+    if file.word()? != FILE_MAGIC {
+        return Err(AsepriteError("bad magic number".into()));
+    }
+    let frame_count = file.word()? as usize;
+    let width = file.word()? as usize;
+    let height = file.word()? as usize;
+    let depth = file.word()?;
+    if !matches!(depth, 8 | 16 | 32) {
+        return Err(AsepriteError(format!("unsupported color depth {depth}")));
+    }
+    let sheet_width = width * frame_count;
+    if sheet_width > u16::MAX as usize {
+        return Err(AsepriteError(format!(
+            "{frame_count} frames of {width} pixels are too wide for one sheet"
+        )));
+    }
+    file.take(14)?;
+    let transparent = file.byte()?;
+    file.take(128 - 29)?;
+
+    let mut layers: Vec<Layer> = Vec::new();
+    // Visibility and opacity of the enclosing group at each nesting level.
+    let mut parents: Vec<(bool, u8)> = Vec::new();
+    let mut palette = vec![[0u8; 4]; 256];
+    let mut sheet = SpriteSheet::default();
+    let mut cels: Vec<Vec<Cel>> = Vec::with_capacity(frame_count);
+
+    for frame in 0..frame_count {
+        let start = file.position;
+        let size = file.dword()? as usize;
+        if file.word()? != FRAME_MAGIC {
+            return Err(AsepriteError(format!("bad magic number in frame {frame}")));
+        }
+        let old_chunks = file.word()? as usize;
+        let duration = file.word()? as f32 / 1000.0;
+        file.take(2)?;
+        let chunks = match file.dword()? as usize {
+            0 => old_chunks,
+            chunks => chunks,
+        };
+        let source = Rect::new((frame * width) as f32, 0.0, width as f32, height as f32);
+        sheet.frames.push((source, duration));
+        let mut frame_cels = Vec::new();
+
+        for _ in 0..chunks {
+            let chunk_size = file.dword()? as usize;
+            let kind = file.word()?;
+            let mut chunk = Reader {
+                bytes: file.take(chunk_size.saturating_sub(6))?,
+                position: 0,
+            };
+            match kind {
+                CHUNK_LAYER => {
+                    let flags = chunk.word()?;
+                    let layer_type = chunk.word()?;
+                    let level = chunk.word()? as usize;
+                    chunk.take(6)?;
+                    let opacity = chunk.byte()?;
+                    parents.truncate(level);
+                    let (parent_visible, parent_opacity) =
+                        parents.last().copied().unwrap_or((true, 255));
+                    let visible = parent_visible && flags & 1 != 0;
+                    let opacity = (opacity as u32 * parent_opacity as u32 / 255) as u8;
+                    let group = layer_type == 1;
+                    if group {
+                        parents.push((visible, opacity));
+                    }
+                    layers.push(Layer {
+                        visible,
+                        opacity,
+                        group,
+                    });
+                }
+                CHUNK_CEL => {
+                    let layer = chunk.word()? as usize;
+                    let x = chunk.short()? as i32;
+                    let y = chunk.short()? as i32;
+                    let opacity = chunk.byte()?;
+                    let cel_type = chunk.word()?;
+                    chunk.take(7)?;
+                    let data = match cel_type {
+                        0 => {
+                            let (width, height) = (chunk.word()? as usize, chunk.word()? as usize);
+                            let length = width * height * depth as usize / 8;
+                            let pixels = chunk.take(length)?.to_vec();
+                            CelData::Pixels {
+                                width,
+                                height,
+                                pixels,
+                            }
+                        }
+                        1 => CelData::Linked(chunk.word()? as usize),
+                        2 => {
+                            let (width, height) = (chunk.word()? as usize, chunk.word()? as usize);
+                            let rest = &chunk.bytes[chunk.position..];
+                            let pixels = decompress_to_vec_zlib(rest)
+                                .map_err(|e| AsepriteError(format!("bad compressed cel: {e:?}")))?;
+                            CelData::Pixels {
+                                width,
+                                height,
+                                pixels,
+                            }
+                        }
+                        // Tilemap cels are not supported and are left out.
+                        _ => continue,
+                    };
+                    frame_cels.push(Cel {
+                        layer,
+                        x,
+                        y,
+                        opacity,
+                        data,
+                    });
+                }
+                CHUNK_TAGS => {
+                    let count = chunk.word()?;
+                    chunk.take(8)?;
+                    for _ in 0..count {
+                        let from = chunk.word()? as usize;
+                        let to = chunk.word()? as usize;
+                        let direction = match chunk.byte()? {
+                            1 => TagDirection::Reverse,
+                            2 => TagDirection::PingPong,
+                            3 => TagDirection::PingPongReverse,
+                            _ => TagDirection::Forward,
+                        };
+                        chunk.take(12)?;
+                        let name = chunk.string()?;
+                        let mut frames = (from..=to).collect::<Vec<usize>>();
+                        if let TagDirection::Reverse | TagDirection::PingPongReverse = direction {
+                            frames.reverse();
+                        }
+                        // Ping-pong plays back the way it came without repeating either end.
+                        if let TagDirection::PingPong | TagDirection::PingPongReverse = direction {
+                            let back = frames.iter().rev().skip(1);
+                            let back = back.take(frames.len().saturating_sub(2));
+                            frames.extend(back.copied().collect::<Vec<usize>>());
+                        }
+                        sheet.tags.push(FrameTag { name, frames });
+                    }
+                }
+                CHUNK_PALETTE => {
+                    let _size = chunk.dword()?;
+                    let first = chunk.dword()? as usize;
+                    let last = chunk.dword()? as usize;
+                    chunk.take(8)?;
+                    for index in first..=last {
+                        let flags = chunk.word()?;
+                        let color = [chunk.byte()?, chunk.byte()?, chunk.byte()?, chunk.byte()?];
+                        if let Some(entry) = palette.get_mut(index) {
+                            *entry = color;
+                        }
+                        if flags & 1 != 0 {
+                            chunk.string()?;
+                        }
+                    }
+                }
+                CHUNK_OLD_PALETTE if palette.iter().all(|color| color[3] == 0) => {
+                    let mut index = 0;
+                    for _ in 0..chunk.word()? {
+                        index += chunk.byte()? as usize;
+                        let count = match chunk.byte()? {
+                            0 => 256,
+                            count => count as usize,
+                        };
+                        for _ in 0..count {
+                            let color = [chunk.byte()?, chunk.byte()?, chunk.byte()?, 255];
+                            if let Some(entry) = palette.get_mut(index) {
+                                *entry = color;
+                            }
+                            index += 1;
+                        }
+                    }
+                }
+                CHUNK_SLICE => {
+                    let keys = chunk.dword()?;
+                    let flags = chunk.dword()?;
+                    chunk.dword()?;
+                    let name = chunk.string()?;
+                    for key in 0..keys {
+                        chunk.dword()?;
+                        let (x, y) = (chunk.long()?, chunk.long()?);
+                        let (w, h) = (chunk.dword()?, chunk.dword()?);
+                        if key == 0 {
+                            let bounds = Rect::new(x as f32, y as f32, w as f32, h as f32);
+                            sheet.slices.insert(name.clone(), bounds);
+                        }
+                        if flags & 1 != 0 {
+                            chunk.take(16)?;
+                        }
+                        if flags & 2 != 0 {
+                            chunk.take(8)?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        file.position = start + size;
+        cels.push(frame_cels);
+    }
+
+    let rgba = |pixel: &[u8]| -> [u8; 4] {
+        match depth {
+            32 => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            16 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+            _ if pixel[0] == transparent => [0; 4],
+            _ => palette[pixel[0] as usize],
+        }
+    };
+    let mut image = vec![0u8; sheet_width * height * 4];
+    for (frame, frame_cels) in cels.iter().enumerate() {
+        let mut ordered = frame_cels.iter().collect::<Vec<&Cel>>();
+        ordered.sort_by_key(|cel| cel.layer);
+        for cel in ordered {
+            let layer = match layers.get(cel.layer) {
+                Some(layer) if layer.visible && !layer.group => layer,
+                _ => continue,
+            };
+            let (cel_width, cel_height, pixels) = match &cel.data {
+                CelData::Pixels {
+                    width,
+                    height,
+                    pixels,
+                } => (*width, *height, pixels),
+                CelData::Linked(source) => match cels.get(*source).and_then(|source| {
+                    source.iter().find_map(|linked| match &linked.data {
+                        CelData::Pixels {
+                            width,
+                            height,
+                            pixels,
+                        } if linked.layer == cel.layer => Some((*width, *height, pixels)),
+                        _ => None,
+                    })
+                }) {
+                    Some(found) => found,
+                    None => continue,
+                },
+            };
+            let opacity = cel.opacity as f32 / 255.0 * layer.opacity as f32 / 255.0;
+            let stride = depth as usize / 8;
+            for row in 0..cel_height {
+                for column in 0..cel_width {
+                    let (x, y) = (cel.x + column as i32, cel.y + row as i32);
+                    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+                        continue;
+                    }
+                    let offset = (row * cel_width + column) * stride;
+                    let Some(pixel) = pixels.get(offset..offset + stride) else {
+                        continue;
+                    };
+                    let source = rgba(pixel);
+                    let target = (y as usize * sheet_width + frame * width + x as usize) * 4;
+                    blend(&mut image[target..target + 4], source, opacity);
+                }
+            }
+        }
+    }
+
+    let image = Image {
+        bytes: image,
+        width: sheet_width as u16,
+        height: height as u16,
+    };
+    Ok((image, sheet))
+}
+
+/// Normal "source over" blending; other Aseprite blend modes are drawn as normal.
+fn blend(target: &mut [u8], source: [u8; 4], opacity: f32) {
+    let alpha = source[3] as f32 / 255.0 * opacity;
+    if alpha <= 0.0 {
+        return;
+    }
+    let below = target[3] as f32 / 255.0;
+    let out = alpha + below * (1.0 - alpha);
+    for channel in 0..3 {
+        let mixed = source[channel] as f32 * alpha + target[channel] as f32 * below * (1.0 - alpha);
+        target[channel] = (mixed / out).round() as u8;
+    }
+    target[3] = (out * 255.0).round() as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use macroquad::prelude::{ImageFormat, Rect};
+
+    use super::{
+        load, Image, SpriteSheet, CHUNK_CEL, CHUNK_LAYER, CHUNK_SLICE, CHUNK_TAGS, FILE_MAGIC,
+        FRAME_MAGIC,
+    };
+
+    fn load_file(name: &str) -> (Image, SpriteSheet) {
+        let bytes = fs::read(format!("res/{name}.aseprite")).unwrap();
+        load(&bytes).unwrap()
+    }
+
+    fn chunk(kind: u16, body: &[u8]) -> Vec<u8> {
+        [
+            &(body.len() as u32 + 6).to_le_bytes()[..],
+            &kind.to_le_bytes(),
+            body,
+        ]
+        .concat()
+    }
+
+    fn string(text: &str) -> Vec<u8> {
+        [&(text.len() as u16).to_le_bytes()[..], text.as_bytes()].concat()
+    }
+
+    /// An RGBA file of one-pixel-high frames on one layer. Each frame has a single
+    /// pixel, at its left edge, whose red is the frame number, and lasts that many
+    /// tenths of a second. `extra` chunks go in the first frame.
+    fn build(width: u16, frames: u16, extra: &[Vec<u8>]) -> Vec<u8> {
+        // File size, which the loader ignores, then magic, frame count, size and depth.
+        let mut file = 0u32.to_le_bytes().to_vec();
+        for word in [FILE_MAGIC, frames, width, 1, 32] {
+            file.extend(word.to_le_bytes());
+        }
+        file.resize(128, 0);
+        for frame in 0..frames {
+            let mut chunks = Vec::new();
+            if frame == 0 {
+                // Visible normal layer at full opacity.
+                let layer = [
+                    &[1, 0, 0, 0, 0, 0][..],
+                    &[0; 6],
+                    &[255, 0, 0, 0],
+                    &string("Layer"),
+                ];
+                chunks.push(chunk(CHUNK_LAYER, &layer.concat()));
+                chunks.extend(extra.iter().cloned());
+            }
+            // Raw 1x1 cel at the origin of layer 0.
+            let cel = [
+                &[0; 6][..],
+                &[255, 0, 0],
+                &[0; 7],
+                &[1, 0, 1, 0],
+                &[frame as u8, 0, 0, 255],
+            ];
+            chunks.push(chunk(CHUNK_CEL, &cel.concat()));
+            let body = chunks.concat();
+            file.extend((body.len() as u32 + 16).to_le_bytes());
+            for word in [FRAME_MAGIC, chunks.len() as u16, frame * 100, 0] {
+                file.extend(word.to_le_bytes());
+            }
+            file.extend((chunks.len() as u32).to_le_bytes());
+            file.extend(body);
+        }
+        file
+    }
+
+    /// Compares the top `rows` rows with a hand-exported PNG, ignoring the color of
+    /// fully transparent pixels.
+    fn assert_matches_export(image: &Image, png: &str, rows: usize) {
+        let bytes = fs::read(format!("res/{png}")).unwrap();
+        let export = Image::from_file_with_format(&bytes, Some(ImageFormat::Png));
+        assert_eq!(image.width, export.width);
+        let len = image.width as usize * rows * 4;
+        let pixels = image.bytes[..len]
+            .chunks(4)
+            .zip(export.bytes[..len].chunks(4));
+        for (i, (ours, theirs)) in pixels.enumerate() {
+            let same = ours[3] == theirs[3] && (ours[3] == 0 || ours == theirs);
+            assert!(same, "pixel {i} differs: {ours:?} vs {theirs:?}");
+        }
+    }
+
+    #[test]
+    fn ball_matches_export() {
+        let (image, _) = load_file("ball");
+        assert_eq!((image.width, image.height), (30, 30));
+        assert_matches_export(&image, "ball.png", 30);
+    }
+
+    #[test]
+    fn blocks_match_export() {
+        let (image, sheet) = load_file("blocks");
+        assert_eq!((image.width, image.height), (500, 40));
+        assert_matches_export(&image, "blocks-Sheet.png", 40);
+        let frames = sheet.frames.iter().map(|(source, _)| *source);
+        let expected = (0..5).map(|i| Rect::new(i as f32 * 100.0, 0.0, 100.0, 40.0));
+        assert!(frames.eq(expected));
+        assert!(sheet.frames.iter().all(|(_, duration)| *duration == 0.1));
+    }
+
+    #[test]
+    fn player_matches_visible_rows_of_export() {
+        let (image, _) = load_file("player");
+        assert_eq!((image.width, image.height), (150, 8));
+        assert_matches_export(&image, "player.png", 4);
+    }
+
+    #[test]
+    fn lays_frames_out_left_to_right() {
+        let (image, sheet) = load(&build(1, 3, &[])).unwrap();
+        assert_eq!((image.width, image.height), (3, 1));
+        let reds = image.bytes.chunks(4).map(|pixel| pixel[0]);
+        assert!(reds.eq([0, 1, 2]));
+        let expected = (0..3).map(|i| (Rect::new(i as f32, 0.0, 1.0, 1.0), i as f32 / 10.0));
+        assert!(sheet.frames.into_iter().eq(expected));
+    }
+
+    #[test]
+    fn unrolls_tags_in_each_direction() {
+        let tags = [
+            "Forward",
+            "Reverse",
+            "PingPong",
+            "PingPongReverse",
+            "Single",
+        ]
+        .iter()
+        .enumerate()
+        .flat_map(|(direction, name)| {
+            let to = if *name == "Single" { 1u16 } else { 3 };
+            let range = [1u16.to_le_bytes(), to.to_le_bytes()].concat();
+            [range, vec![direction as u8], vec![0; 12], string(name)].concat()
+        })
+        .collect::<Vec<u8>>();
+        let body = [&5u16.to_le_bytes()[..], &[0; 8], &tags].concat();
+        let (_, sheet) = load(&build(1, 4, &[chunk(CHUNK_TAGS, &body)])).unwrap();
+        let tags = sheet
+            .tags
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.frames.clone()))
+            .collect::<Vec<(&str, Vec<usize>)>>();
+        assert_eq!(
+            tags,
+            [
+                ("Forward", vec![1, 2, 3]),
+                ("Reverse", vec![3, 2, 1]),
+                ("PingPong", vec![1, 2, 3, 2]),
+                ("PingPongReverse", vec![3, 2, 1, 2]),
+                // Out-of-range directions play forward.
+                ("Single", vec![1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_slice_bounds_from_the_first_key() {
+        let key = |frame: u32, x: i32, w: u32| {
+            [frame.to_le_bytes(), x.to_le_bytes(), 2i32.to_le_bytes()]
+                .concat()
+                .into_iter()
+                .chain([w.to_le_bytes(), 4u32.to_le_bytes()].concat())
+                .collect::<Vec<u8>>()
+        };
+        let header = |keys: u32, flags: u32, name: &str| {
+            [keys.to_le_bytes(), flags.to_le_bytes(), [0; 4]]
+                .concat()
+                .into_iter()
+                .chain(string(name))
+                .collect::<Vec<u8>>()
+        };
+        let paddle = [header(2, 0, "Paddle"), key(0, 1, 3), key(1, 5, 7)].concat();
+        // Nine-patch and pivot data follow each key.
+        let nine_patch = [header(1, 3, "Panel"), key(0, -1, 9), vec![0; 24]].concat();
+        let chunks = [chunk(CHUNK_SLICE, &paddle), chunk(CHUNK_SLICE, &nine_patch)];
+        let (_, sheet) = load(&build(1, 2, &chunks)).unwrap();
+        assert_eq!(sheet.slices["Paddle"], Rect::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(sheet.slices["Panel"], Rect::new(-1.0, 2.0, 9.0, 4.0));
+    }
+
+    #[test]
+    fn rejects_sheets_wider_than_a_texture() {
+        let error = load(&build(1000, 66, &[])).err().unwrap();
+        assert!(error.0.contains("too wide"));
+        assert!(load(&build(1000, 65, &[])).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = fs::read("res/ball.aseprite").unwrap();
+        bytes[4] = 0;
+        let error = load(&bytes).err().unwrap();
+        assert!(error.0.contains("magic"));
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let bytes = fs::read("res/blocks.aseprite").unwrap();
+        for len in [0, 64, 200, bytes.len() - 1] {
+            assert!(load(&bytes[..len]).is_err(), "accepted {len} bytes");
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Index,
+};
+
+use macroquad::{audio::Sound, text::Font, texture::Texture2D};
+
+use super::aseprite::SpriteSheet;
+
+/// A cheap, copyable reference to an asset in an `AssetStore`. Handles are only
+/// handed out by the store, so looking one up cannot fail.
+pub struct Handle<T> {
+    index: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({})", self.index)
+    }
+}
+
+/// Assets of one kind, addressed by key while loading and by handle afterwards.
+#[derive(Clone)]
+pub struct AssetStore<T> {
+    keys: HashMap<String, Handle<T>>,
+    assets: Vec<T>,
+}
+
+impl<T> Default for AssetStore<T> {
+    fn default() -> Self {
+        AssetStore {
+            keys: HashMap::new(),
+            assets: Vec::new(),
+        }
+    }
+}
+
+impl<T> AssetStore<T> {
+    /// Adds an asset under `key`. An existing key keeps its handle and gets the new asset.
+    pub fn insert(&mut self, key: String, asset: T) -> Handle<T> {
+        if let Some(handle) = self.keys.get(&key) {
+            self.assets[handle.index] = asset;
+            return *handle;
+        }
+        let handle = Handle {
+            index: self.assets.len(),
+            marker: PhantomData,
+        };
+        self.assets.push(asset);
+        self.keys.insert(key, handle);
+        handle
+    }
+
+    pub fn handle(&self, key: &str) -> Option<Handle<T>> {
+        self.keys.get(key).copied()
+    }
+
+    /// Like `handle`, but records a missing key in `errors`.
+    pub fn require(&self, key: &str, errors: &mut Vec<String>) -> Option<Handle<T>> {
+        let handle = self.handle(key);
+        if handle.is_none() {
+            errors.push(format!("{key}: not in the asset manifest"));
+        }
+        handle
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> &T {
+        &self.assets[handle.index]
+    }
+}
+
+impl<T> Index<Handle<T>> for AssetStore<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle<T>) -> &T {
+        self.get(handle)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Texture,
+    Sound,
+    Font,
+}
+
+/// Every loaded asset, shared with each scene as a resource.
+#[derive(Clone, Default)]
+pub struct AssetRegistry {
+    pub textures: AssetStore<Texture2D>,
+    pub sounds: AssetStore<Sound>,
+    pub fonts: AssetStore<Font>,
+    /// Frame layouts of textures loaded from Aseprite files, under the texture's key.
+    pub sheets: AssetStore<SpriteSheet>,
+    /// Detuned copies of a sound, played in its place now and then.
+    pub sound_variants: HashMap<Handle<Sound>, Vec<Sound>>,
+}
+
+impl AssetRegistry {
+    pub fn contains(&self, kind: AssetKind, key: &str) -> bool {
+        match kind {
+            AssetKind::Texture => self.textures.handle(key).is_some(),
+            AssetKind::Sound => self.sounds.handle(key).is_some(),
+            AssetKind::Font => self.fonts.handle(key).is_some(),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::{collections::HashMap, error::Error};
+
+use macroquad::{
+    audio::{
+        load_sound, load_sound_from_bytes, play_sound, set_sound_volume, stop_sound,
+        PlaySoundParams, Sound,
+    },
+    file::load_file,
+    rand::gen_range,
+    time::get_time,
+};
+
+use super::{
+    asset::{AssetRegistry, AssetStore, Handle},
+    wav::repitch,
+};
+
+/// macroquad cannot tell when a sound has finished, so a voice is assumed to
+/// occupy its slot for this long after it starts.
+pub const VOICE_DURATION: f64 = 0.3;
+/// Seconds it takes for one music track to fade into the next.
+pub const CROSSFADE_DURATION: f32 = 1.5;
+/// Music volume multiplier while ducked, e.g. behind the pause menu.
+pub const DUCK_VOLUME: f32 = 0.3;
+/// Pitches of the copies made of every `.wav` sound. macroquad cannot change pitch
+/// on playback, so `AudioManager` picks between the copies and the original instead.
+pub const PITCH_VARIATION: [f32; 4] = [0.92, 0.96, 1.04, 1.08];
+
+struct MusicVoice {
+    track: Handle<Sound>,
+    sound: Sound,
+    fade: f32,
+}
+
+/// Plays sounds on behalf of the game systems. Requests are queued during the
+/// frame and played by `update`, which applies the channel volumes, limits how
+/// many copies of one sound can overlap and crossfades between music tracks.
+pub struct AudioManager {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub muted: bool,
+    /// Maximum number of overlapping copies of the same sound effect.
+    pub max_voices: usize,
+    /// Lowers the music volume while set.
+    pub ducked: bool,
+    target_music: Option<Handle<Sound>>,
+    music: Option<MusicVoice>,
+    fading: Vec<MusicVoice>,
+    duck: f32,
+    queue: Vec<(Handle<Sound>, f32)>,
+    voices: HashMap<Handle<Sound>, Vec<f64>>,
+}
+
+impl Default for AudioManager {
+    fn default() -> Self {
+        AudioManager {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 1.0,
+            muted: false,
+            max_voices: 3,
+            ducked: false,
+            target_music: None,
+            music: None,
+            fading: Vec::new(),
+            duck: 1.0,
+            queue: Vec::new(),
+            voices: HashMap::new(),
+        }
+    }
+}
+
+impl AudioManager {
+    /// Queues a sound effect; `volume` scales the SFX channel volume.
+    pub fn play_sfx(&mut self, sound: Handle<Sound>, volume: f32) {
+        self.queue.push((sound, volume));
+    }
+
+    pub fn effective_sfx_volume(&self) -> f32 {
+        self.channel_volume(self.sfx_volume)
+    }
+
+    pub fn effective_music_volume(&self) -> f32 {
+        self.channel_volume(self.music_volume)
+    }
+
+    /// Loops the track, crossfading from whatever is playing; `None` fades to silence.
+    /// Requesting the current track again does nothing.
+    pub fn play_music(&mut self, track: Option<Handle<Sound>>) {
+        self.target_music = track;
+    }
+
+    pub fn update(&mut self, delta: f32, registry: &AssetRegistry) {
+        self.flush(registry);
+        self.update_music(delta, &registry.sounds);
+    }
+
+    fn flush(&mut self, registry: &AssetRegistry) {
+        let now = get_time();
+        let volume = self.effective_sfx_volume();
+        for (sound, scale) in std::mem::take(&mut self.queue) {
+            let voices = self.voices.entry(sound).or_default();
+            voices.retain(|start| now - *start < VOICE_DURATION);
+            if voices.len() >= self.max_voices || volume <= 0.0 {
+                continue;
+            }
+            let variants = registry
+                .sound_variants
+                .get(&sound)
+                .map_or(&[][..], Vec::as_slice);
+            let pick = gen_range(0, variants.len() + 1);
+            play_sound(
+                variants
+                    .get(pick)
+                    .copied()
+                    .unwrap_or(registry.sounds[sound]),
+                PlaySoundParams {
+                    looped: false,
+                    volume: volume * scale,
+                },
+            );
+            voices.push(now);
+        }
+    }
+
+    fn update_music(&mut self, delta: f32, sounds: &AssetStore<Sound>) {
+        if self.music.as_ref().map(|voice| voice.track) != self.target_music {
+            self.fading.extend(self.music.take());
+            if let Some(track) = self.target_music {
+                // Bring back a track that is still fading out instead of restarting it.
+                if let Some(index) = self.fading.iter().position(|voice| voice.track == track) {
+                    self.music = Some(self.fading.remove(index));
+                } else {
+                    let sound = sounds[track];
+                    play_sound(
+                        sound,
+                        PlaySoundParams {
+                            looped: true,
+                            volume: 0.0,
+                        },
+                    );
+                    self.music = Some(MusicVoice {
+                        track,
+                        sound,
+                        fade: 0.0,
+                    });
+                }
+            }
+        }
+
+        let step = delta / CROSSFADE_DURATION;
+        let duck_target = if self.ducked { DUCK_VOLUME } else { 1.0 };
+        self.duck = if self.duck < duck_target {
+            (self.duck + step).min(duck_target)
+        } else {
+            (self.duck - step).max(duck_target)
+        };
+        let volume = self.effective_music_volume() * self.duck;
+
+        if let Some(voice) = &mut self.music {
+            voice.fade = (voice.fade + step).min(1.0);
+            set_sound_volume(voice.sound, volume * voice.fade);
+        }
+        self.fading.retain_mut(|voice| {
+            voice.fade -= step;
+            if voice.fade <= 0.0 {
+                stop_sound(voice.sound);
+                return false;
+            }
+            set_sound_volume(voice.sound, volume * voice.fade);
+            true
+        });
+    }
+
+    fn channel_volume(&self, channel: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * channel
+        }
+    }
+}
+
+/// Loads a sound, resampled by `pitch` if given. A `.wav` file also gets a copy
+/// for every `PITCH_VARIATION`.
+pub async fn load_sfx(
+    path: &str,
+    pitch: Option<f32>,
+) -> Result<(Sound, Vec<Sound>), Box<dyn Error>> {
+    if !path.ends_with(".wav") {
+        if pitch.is_some() {
+            return Err("only .wav files can be repitched".into());
+        }
+        return Ok((load_sound(path).await?, Vec::new()));
+    }
+    let bytes = load_file(path).await?;
+    let pitch = pitch.unwrap_or(1.0);
+    let sound = load_sound_from_bytes(&repitch(&bytes, pitch)?).await?;
+    let mut variants = Vec::new();
+    for variation in PITCH_VARIATION {
+        variants.push(load_sound_from_bytes(&repitch(&bytes, pitch * variation)?).await?);
+    }
+    Ok((sound, variants))
+}
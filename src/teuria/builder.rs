@@ -1,57 +1,142 @@
-use std::collections::HashMap;
+use std::path::Path;
 
-use macroquad::{
-    audio::{load_sound, Sound},
-    prelude::*,
-};
+use macroquad::prelude::*;
 use macroquad_canvas::Canvas2D;
 
-use crate::game::WorldScene;
+#[cfg(feature = "dev")]
+use super::hot_reload::HotReload;
+use super::{
+    aseprite::load_sheet,
+    asset::{AssetKind, AssetRegistry},
+    audio::load_sfx,
+    manifest::{AssetErrors, AssetManifest, ManifestEntry},
+    scene::{Assets, SceneFactory, SceneStack},
+};
+
+/// Key of the font every scene draws its text with.
+pub const DEFAULT_FONT: &str = "Main";
 
 pub struct GameWorldBuilder {
-    textures: HashMap<String, Texture2D>,
-    audios: HashMap<String, Sound>,
-    font: Option<Font>,
+    registry: AssetRegistry,
     resolution: Canvas2D,
+    required: Vec<(AssetKind, String)>,
+    #[cfg(feature = "dev")]
+    hot_reload: HotReload,
 }
 
 impl GameWorldBuilder {
     pub fn new(resolution: Canvas2D) -> GameWorldBuilder {
         GameWorldBuilder {
-            textures: HashMap::new(),
-            audios: HashMap::new(),
-            font: None,
+            registry: AssetRegistry::default(),
             resolution,
+            required: vec![(AssetKind::Font, DEFAULT_FONT.into())],
+            #[cfg(feature = "dev")]
+            hot_reload: HotReload::default(),
         }
     }
 
-    pub async fn add_audio(
-        &mut self,
-        audio_name: String,
-        audio_path: &str,
-    ) -> Result<&mut GameWorldBuilder, FileError> {
-        self.audios
-            .insert(audio_name, load_sound(audio_path).await?);
-        Ok(self)
+    /// Keys `load_manifest` must end up with; missing ones are reported with the other failures.
+    pub fn require(&mut self, kind: AssetKind, keys: &[&str]) -> &mut GameWorldBuilder {
+        self.required
+            .extend(keys.iter().map(|key| (kind, key.to_string())));
+        self
     }
 
-    pub async fn add_texture(
+    /// Loads every asset in the manifest, preferring files from `skin_dir`.
+    /// Keeps going past failures so all of them are reported together.
+    pub async fn load_manifest(
         &mut self,
-        texture_name: String,
-        texture_path: &str,
-    ) -> Result<&mut GameWorldBuilder, FileError> {
-        self.textures
-            .insert(texture_name, load_texture(texture_path).await?);
+        manifest: &AssetManifest,
+        skin_dir: Option<&Path>,
+    ) -> Result<&mut GameWorldBuilder, AssetErrors> {
+        let mut errors = Vec::new();
+        for kind in [AssetKind::Texture, AssetKind::Sound, AssetKind::Font] {
+            for entry in manifest.entries(kind) {
+                load_entry(&mut self.registry, kind, entry, skin_dir, &mut errors).await;
+                self.track(kind, entry, skin_dir);
+            }
+        }
+        for (kind, key) in &self.required {
+            if self.registry.contains(*kind, key) {
+                continue;
+            }
+            // Required entries that failed to load have already reported why.
+            match manifest
+                .entries(*kind)
+                .iter()
+                .find(|entry| entry.key == *key)
+            {
+                Some(entry) if !entry.optional => {}
+                Some(_) => errors.push(format!("{key}: needed by the game but marked optional")),
+                None => errors.push(format!("{key}: not in the asset manifest")),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(AssetErrors(errors));
+        }
         Ok(self)
     }
 
-    pub fn insert_font(&mut self, font: Font) -> &mut GameWorldBuilder {
-        self.font = Some(font);
-        self
+    /// Remembers where an asset comes from so the dev build can reload it, or load it
+    /// once a missing file shows up.
+    #[cfg(feature = "dev")]
+    fn track(&mut self, kind: AssetKind, entry: &ManifestEntry, skin_dir: Option<&Path>) {
+        self.hot_reload.skin_dir = skin_dir.map(Path::to_path_buf);
+        self.hot_reload.track(kind, entry);
     }
 
-    pub fn build(&mut self) -> WorldScene {
-        let textures = self.textures.clone();
-        WorldScene::new(self.font, self.resolution, textures, self.audios.clone())
+    #[cfg(not(feature = "dev"))]
+    fn track(&mut self, _kind: AssetKind, _entry: &ManifestEntry, _skin_dir: Option<&Path>) {}
+
+    pub fn build(&mut self, initial: SceneFactory) -> SceneStack {
+        let font = self.registry.fonts.handle(DEFAULT_FONT).unwrap();
+        let assets = Assets {
+            font: self.registry.fonts[font],
+            registry: self.registry.clone(),
+            resolution: self.resolution,
+        };
+        #[allow(unused_mut)]
+        let mut stack = SceneStack::new(assets, initial);
+        #[cfg(feature = "dev")]
+        {
+            stack.hot_reload = std::mem::take(&mut self.hot_reload);
+        }
+        stack
+    }
+}
+
+/// Loads one manifest entry into the registry, returning the path it came from.
+/// Reloading an entry keeps its handle.
+pub(super) async fn load_entry(
+    registry: &mut AssetRegistry,
+    kind: AssetKind,
+    entry: &ManifestEntry,
+    skin_dir: Option<&Path>,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let key = entry.key.clone();
+    match kind {
+        AssetKind::Texture => {
+            let load = |path: String| async move { load_sheet(&path).await };
+            let ((texture, sheet), path) = entry.load(skin_dir, load, errors).await?;
+            registry.textures.insert(key.clone(), texture);
+            if let Some(sheet) = sheet {
+                registry.sheets.insert(key, sheet);
+            }
+            Some(path)
+        }
+        AssetKind::Sound => {
+            let load = |path: String| async move { load_sfx(&path, entry.pitch).await };
+            let ((sound, variants), path) = entry.load(skin_dir, load, errors).await?;
+            let handle = registry.sounds.insert(key, sound);
+            registry.sound_variants.insert(handle, variants);
+            Some(path)
+        }
+        AssetKind::Font => {
+            let load = |path: String| async move { load_ttf_font(&path).await };
+            let (font, path) = entry.load(skin_dir, load, errors).await?;
+            registry.fonts.insert(key, font);
+            Some(path)
+        }
     }
 }
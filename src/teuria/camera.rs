@@ -0,0 +1,75 @@
+use macroquad::{
+    prelude::{vec2, Camera2D, Vec2},
+    rand::gen_range,
+};
+
+/// Largest shake displacement in canvas pixels, reached at full trauma.
+pub const MAX_SHAKE_OFFSET: f32 = 12.0;
+/// Trauma lost per second.
+pub const TRAUMA_DECAY: f32 = 1.5;
+
+/// Cameras available while a scene draws: `world` includes any screen shake,
+/// `screen` is the fixed canvas camera for UI that must stay put.
+pub struct SceneCameras {
+    pub world: Camera2D,
+    pub screen: Camera2D,
+}
+
+/// Screen shake and hit-stop for the scene that owns it. Shake strength is the
+/// square of `trauma`, so small bumps stay subtle while big hits stack up.
+pub struct CameraEffects {
+    /// Turned off for players who asked for reduced motion.
+    pub enabled: bool,
+    trauma: f32,
+    offset: Vec2,
+    hit_stop: u32,
+}
+
+impl Default for CameraEffects {
+    fn default() -> Self {
+        CameraEffects {
+            enabled: true,
+            trauma: 0.0,
+            offset: Vec2::ZERO,
+            hit_stop: 0,
+        }
+    }
+}
+
+impl CameraEffects {
+    pub fn add_trauma(&mut self, amount: f32) {
+        if self.enabled {
+            self.trauma = (self.trauma + amount).min(1.0);
+        }
+    }
+
+    /// Freezes the simulation for the given number of frames.
+    pub fn hit_stop(&mut self, frames: u32) {
+        if self.enabled {
+            self.hit_stop = self.hit_stop.max(frames);
+        }
+    }
+
+    /// Advances the effects by one frame, returning whether the simulation is frozen.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        if !self.enabled {
+            self.trauma = 0.0;
+            self.hit_stop = 0;
+        }
+        let shake = self.trauma * self.trauma * MAX_SHAKE_OFFSET;
+        self.offset = vec2(gen_range(-1.0, 1.0), gen_range(-1.0, 1.0)) * shake;
+        self.trauma = (self.trauma - TRAUMA_DECAY * delta).max(0.0);
+        if self.hit_stop > 0 {
+            self.hit_stop -= 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn apply(&self, camera: &Camera2D) -> Camera2D {
+        Camera2D {
+            target: camera.target + self.offset,
+            ..*camera
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use macroquad::time::get_time;
+
+use super::{asset::AssetKind, manifest::ManifestEntry};
+
+/// Seconds between checks for modified files.
+pub const POLL_INTERVAL: f64 = 0.5;
+
+struct Watched<T> {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    target: T,
+}
+
+impl<T> Watched<T> {
+    fn new(path: PathBuf, target: T) -> Self {
+        Watched {
+            modified: modified(&path),
+            path,
+            target,
+        }
+    }
+
+    /// Also true when a file that did not exist before shows up.
+    fn changed(&mut self) -> bool {
+        let modified = modified(&self.path);
+        if modified.is_some() && modified != self.modified {
+            self.modified = modified;
+            return true;
+        }
+        false
+    }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Polls the asset manifest, the files it lists and other watched files for changes.
+#[derive(Default)]
+pub struct HotReload {
+    /// One per file an entry may load from, including ones that do not exist yet.
+    assets: Vec<Watched<(AssetKind, ManifestEntry)>>,
+    files: Vec<Watched<fn(&mut bevy_ecs::world::World)>>,
+    manifest: Option<Watched<()>>,
+    pub skin_dir: Option<PathBuf>,
+    /// Run on every scene after any asset reloads, for state derived from assets.
+    pub on_assets: Vec<fn(&mut bevy_ecs::world::World)>,
+    next_poll: f64,
+    /// Seconds spent reloading during this frame and the previous one.
+    reload_time: (f32, f32),
+}
+
+impl HotReload {
+    /// Watches the skinned file and the regular path of an entry, replacing
+    /// whatever was watched for its key before.
+    pub fn track(&mut self, kind: AssetKind, entry: &ManifestEntry) {
+        self.assets
+            .retain(|watched| watched.target.0 != kind || watched.target.1.key != entry.key);
+        let skinned = self
+            .skin_dir
+            .as_deref()
+            .zip(entry.skin.as_ref())
+            .map(|(dir, file)| dir.join(file));
+        for path in skinned
+            .into_iter()
+            .chain(entry.path.as_ref().map(PathBuf::from))
+        {
+            self.assets.push(Watched::new(path, (kind, entry.clone())));
+        }
+    }
+
+    /// Whether `entry` is watched as it is, rather than under an older version.
+    pub fn tracks(&self, kind: AssetKind, entry: &ManifestEntry) -> bool {
+        self.assets
+            .iter()
+            .any(|watched| watched.target.0 == kind && watched.target.1 == *entry)
+    }
+
+    pub fn watch_manifest(&mut self, path: PathBuf) {
+        self.manifest = Some(Watched::new(path, ()));
+    }
+
+    /// Runs `on_change` on every scene's world whenever the file changes.
+    pub fn watch(&mut self, path: PathBuf, on_change: fn(&mut bevy_ecs::world::World)) {
+        self.files.push(Watched::new(path, on_change));
+    }
+
+    /// Whether enough time has passed since the last poll.
+    pub fn due(&mut self) -> bool {
+        let now = get_time();
+        if now < self.next_poll {
+            return false;
+        }
+        self.next_poll = now + POLL_INTERVAL;
+        true
+    }
+
+    pub fn changed_manifest(&mut self) -> Option<&Path> {
+        let manifest = self.manifest.as_mut()?;
+        manifest.changed().then_some(manifest.path.as_path())
+    }
+
+    /// Entries with a changed or newly created file, once each.
+    pub fn changed_assets(&mut self) -> Vec<(AssetKind, ManifestEntry)> {
+        let mut changed: Vec<(AssetKind, ManifestEntry)> = Vec::new();
+        for watched in &mut self.assets {
+            let (kind, entry) = &watched.target;
+            let seen = changed
+                .iter()
+                .any(|(other, seen)| other == kind && seen.key == entry.key);
+            if watched.changed() && !seen {
+                changed.push(watched.target.clone());
+            }
+        }
+        changed
+    }
+
+    pub fn changed_files(&mut self) -> Vec<fn(&mut bevy_ecs::world::World)> {
+        self.files
+            .iter_mut()
+            .filter_map(|watched| watched.changed().then_some(watched.target))
+            .collect()
+    }
+
+    pub fn add_reload_time(&mut self, seconds: f32) {
+        self.reload_time.1 += seconds;
+    }
+
+    /// Time spent reloading during the frame `get_frame_time` measures, i.e. the
+    /// previous one. Called once per frame.
+    pub fn take_reload_time(&mut self) -> f32 {
+        let (previous, current) = self.reload_time;
+        self.reload_time = (current, 0.0);
+        previous
+    }
+}
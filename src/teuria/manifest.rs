@@ -0,0 +1,112 @@
+use std::{
+    fmt::{self, Display},
+    future::Future,
+    path::Path,
+};
+
+use macroquad::{file::load_string, prelude::warn};
+use serde::Deserialize;
+
+use super::asset::AssetKind;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ManifestEntry {
+    pub key: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    /// File name in a skin directory that replaces `path` when present.
+    #[serde(default)]
+    pub skin: Option<String>,
+    /// Optional assets only log a warning when they cannot be loaded.
+    #[serde(default)]
+    pub optional: bool,
+    /// Sounds only: plays a `.wav` file resampled by this factor, so one recording
+    /// can serve several events.
+    #[serde(default)]
+    pub pitch: Option<f32>,
+}
+
+/// Every asset the game loads, grouped by kind and looked up by key.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AssetManifest {
+    pub textures: Vec<ManifestEntry>,
+    pub sounds: Vec<ManifestEntry>,
+    pub fonts: Vec<ManifestEntry>,
+}
+
+impl AssetManifest {
+    pub async fn load(path: &str) -> Result<AssetManifest, AssetErrors> {
+        let contents = load_string(path)
+            .await
+            .map_err(|e| AssetErrors(vec![format!("{path}: {e}")]))?;
+        toml::from_str(&contents).map_err(|e| AssetErrors(vec![format!("{path}: {e}")]))
+    }
+
+    pub fn entries(&self, kind: AssetKind) -> &[ManifestEntry] {
+        match kind {
+            AssetKind::Texture => &self.textures,
+            AssetKind::Sound => &self.sounds,
+            AssetKind::Font => &self.fonts,
+        }
+    }
+}
+
+/// Every asset that failed to load, so they can all be fixed in one go.
+#[derive(Debug)]
+pub struct AssetErrors(pub Vec<String>);
+
+impl Display for AssetErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load {} asset(s):", self.0.len())?;
+        for error in &self.0 {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AssetErrors {}
+
+impl ManifestEntry {
+    /// Loads the skinned file if there is one, then the regular path, returning the
+    /// asset with the path it came from. Optional assets that fail give `None`.
+    pub(super) async fn load<T, E, F, Fut>(
+        &self,
+        skin_dir: Option<&Path>,
+        load: F,
+        errors: &mut Vec<String>,
+    ) -> Option<(T, String)>
+    where
+        E: Display,
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let skinned = skin_dir
+            .zip(self.skin.as_ref())
+            .map(|(dir, file)| dir.join(file))
+            .filter(|path| path.is_file());
+        if let Some(path) = skinned {
+            let path = path.to_string_lossy().into_owned();
+            match load(path.clone()).await {
+                Ok(asset) => return Some((asset, path)),
+                Err(e) => warn!("Skipping skinned {}: {}", path, e),
+            }
+        }
+
+        let error = match &self.path {
+            Some(path) => match load(path.clone()).await {
+                Ok(asset) => return Some((asset, path.clone())),
+                Err(e) => format!("{} ({}): {}", self.key, path, e),
+            },
+            None if self.optional => return None,
+            None => format!("{}: no path given", self.key),
+        };
+        if self.optional {
+            warn!("Skipping {}", error);
+        } else {
+            errors.push(error);
+        }
+        None
+    }
+}
@@ -1 +1,11 @@
+pub mod aseprite;
+pub mod asset;
+pub mod audio;
 pub mod builder;
+pub mod camera;
+#[cfg(feature = "dev")]
+pub mod hot_reload;
+pub mod manifest;
+pub mod scene;
+pub mod viewport;
+pub mod wav;
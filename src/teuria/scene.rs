@@ -0,0 +1,365 @@
+use bevy_ecs::schedule::{Schedule, Stage};
+use macroquad::{
+    prelude::{get_frame_time, set_camera, set_default_camera, Camera2D, BLACK},
+    text::Font,
+    window::clear_background,
+};
+use macroquad_canvas::Canvas2D;
+
+use super::{
+    asset::AssetRegistry,
+    audio::AudioManager,
+    camera::{CameraEffects, SceneCameras},
+    viewport::{MousePosition, Viewport},
+};
+use crate::{
+    component::resource::{FontResource, Time},
+    game::{GameResult, World},
+};
+
+/// Assets loaded once by the builder and handed to every scene.
+#[derive(Clone)]
+pub struct Assets {
+    pub registry: AssetRegistry,
+    pub font: Font,
+    pub resolution: Canvas2D,
+}
+
+/// Builds a scene. Boxed so a transition can carry what the new scene starts with.
+pub type SceneFactory = Box<dyn FnOnce(&Assets) -> WorldScene + Send + Sync>;
+
+pub enum SceneTransition {
+    Push(SceneFactory),
+    /// Pops the top scene, optionally running a callback on the world revealed underneath.
+    /// A transition requested by the callback is applied right after.
+    Pop(Option<fn(&mut bevy_ecs::world::World)>),
+    Replace(SceneFactory),
+    /// Drops every scene on the stack before pushing the new one.
+    Reset(SceneFactory),
+    Quit,
+}
+
+pub struct SceneRequest(pub Option<SceneTransition>);
+
+/// Uncapped wall-clock length of the previous frame, unlike `Time`.
+pub struct FrameTime(pub f32);
+
+pub struct WorldScene {
+    pub world: bevy_ecs::world::World,
+    pub schedule: Schedule,
+    pub draw_schedule: Schedule,
+    /// Overlay scenes are drawn on top of the scenes beneath them instead of replacing them.
+    pub overlay: bool,
+    /// Runs when the scene becomes the top of the stack again after a pop.
+    pub on_resume: Option<fn(&mut bevy_ecs::world::World)>,
+}
+
+impl WorldScene {
+    pub fn new(assets: &Assets) -> Self {
+        let mut world = bevy_ecs::world::World::new();
+        world.insert_resource::<AssetRegistry>(assets.registry.clone());
+        world.insert_resource::<FontResource>(FontResource(assets.font));
+        world.insert_resource::<Time>(Time(0.0));
+        world.insert_resource::<FrameTime>(FrameTime(0.0));
+        world.insert_resource::<SceneRequest>(SceneRequest(None));
+        world.insert_resource::<MousePosition>(MousePosition::default());
+        WorldScene {
+            world,
+            schedule: Schedule::default(),
+            draw_schedule: Schedule::default(),
+            overlay: false,
+            on_resume: None,
+        }
+    }
+
+    /// Skips the schedule while a `CameraEffects` hit-stop is running.
+    pub fn update(&mut self, delta: f32) {
+        if let Some(mut effects) = self.world.get_resource_mut::<CameraEffects>() {
+            if effects.tick(delta) {
+                return;
+            }
+        }
+        self.world.resource_mut::<Time>().0 = delta;
+        self.schedule.run(&mut self.world);
+    }
+
+    pub fn draw(&mut self, camera: &Camera2D) {
+        let world = match self.world.get_resource::<CameraEffects>() {
+            Some(effects) => effects.apply(camera),
+            None => *camera,
+        };
+        set_camera(&world);
+        self.world.insert_resource(SceneCameras {
+            world,
+            screen: *camera,
+        });
+        self.draw_schedule.run(&mut self.world);
+    }
+
+    fn take_request(&mut self) -> Option<SceneTransition> {
+        self.world.resource_mut::<SceneRequest>().0.take()
+    }
+}
+
+pub struct SceneStack {
+    assets: Assets,
+    /// Shared by every scene; lent to the top scene's world while it updates.
+    audio: AudioManager,
+    /// Shared like `audio`, so a scene can change how the canvas is scaled.
+    viewport: Viewport,
+    initial: Option<SceneFactory>,
+    scenes: Vec<WorldScene>,
+    on_exit: Vec<fn(&mut bevy_ecs::world::World)>,
+    quit: bool,
+    #[cfg(feature = "dev")]
+    pub(super) hot_reload: super::hot_reload::HotReload,
+}
+
+impl SceneStack {
+    pub fn new(assets: Assets, initial: SceneFactory) -> Self {
+        SceneStack {
+            assets,
+            audio: AudioManager::default(),
+            viewport: Viewport::default(),
+            initial: Some(initial),
+            scenes: Vec::new(),
+            on_exit: Vec::new(),
+            quit: false,
+            #[cfg(feature = "dev")]
+            hot_reload: Default::default(),
+        }
+    }
+
+    /// Runs `on_exit` on a scene's world whenever it leaves the stack.
+    pub fn on_scene_exit(&mut self, on_exit: fn(&mut bevy_ecs::world::World)) {
+        self.on_exit.push(on_exit);
+    }
+
+    /// Quits as if the top scene asked to, e.g. when the window is closed.
+    pub fn quit(&mut self) {
+        self.apply(SceneTransition::Quit);
+    }
+
+    /// Runs `on_change` on every scene's world whenever the file at `path` changes.
+    #[cfg(feature = "dev")]
+    pub fn watch(&mut self, path: std::path::PathBuf, on_change: fn(&mut bevy_ecs::world::World)) {
+        self.hot_reload.watch(path, on_change);
+    }
+
+    /// Runs `on_reload` on every scene's world after assets are reloaded.
+    #[cfg(feature = "dev")]
+    pub fn on_asset_reload(&mut self, on_reload: fn(&mut bevy_ecs::world::World)) {
+        self.hot_reload.on_assets.push(on_reload);
+    }
+
+    /// Rereads the asset manifest at `path` whenever it changes, loading entries
+    /// that are new or were edited.
+    #[cfg(feature = "dev")]
+    pub fn watch_manifest(&mut self, path: std::path::PathBuf) {
+        self.hot_reload.watch_manifest(path);
+    }
+
+    /// Reloads modified assets into every scene. Handles stay valid, so entities
+    /// pick up the new textures and sounds on their own; text swaps to a reloaded font.
+    #[cfg(feature = "dev")]
+    pub async fn reload_changed(&mut self) {
+        use macroquad::{
+            prelude::{info, warn},
+            time::get_time,
+        };
+
+        use super::{asset::AssetKind, builder::load_entry, manifest::AssetManifest};
+        use crate::component::FontComponent;
+
+        if !self.hot_reload.due() {
+            return;
+        }
+        let start = get_time();
+        let mut changed = self.hot_reload.changed_assets();
+        if let Some(path) = self.hot_reload.changed_manifest() {
+            let path = path.to_string_lossy().into_owned();
+            match AssetManifest::load(&path).await {
+                Ok(manifest) => {
+                    for kind in [AssetKind::Texture, AssetKind::Sound, AssetKind::Font] {
+                        for entry in manifest.entries(kind) {
+                            if !self.hot_reload.tracks(kind, entry) {
+                                self.hot_reload.track(kind, entry);
+                                changed.push((kind, entry.clone()));
+                            }
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to reload {}", e),
+            }
+        }
+
+        let registry = &mut self.assets.registry;
+        let skin_dir = self.hot_reload.skin_dir.as_deref();
+        let mut reloaded = false;
+        let mut fonts = Vec::new();
+        for (kind, entry) in changed {
+            let old_font = match kind {
+                AssetKind::Font => registry.fonts.handle(&entry.key),
+                _ => None,
+            }
+            .map(|handle| registry.fonts[handle]);
+            let mut errors = Vec::new();
+            match load_entry(registry, kind, &entry, skin_dir, &mut errors).await {
+                Some(path) => {
+                    info!("Reloaded {}", path);
+                    reloaded = true;
+                    if let Some(old) = old_font {
+                        let new = registry
+                            .fonts
+                            .handle(&entry.key)
+                            .map(|handle| registry.fonts[handle]);
+                        fonts.extend(new.map(|new| (old, new)));
+                    }
+                }
+                None => {
+                    for error in errors {
+                        warn!("Failed to reload {}", error);
+                    }
+                }
+            }
+        }
+
+        for (old, new) in &fonts {
+            if self.assets.font == *old {
+                self.assets.font = *new;
+            }
+        }
+        for scene in &mut self.scenes {
+            let world = &mut scene.world;
+            if reloaded {
+                world.insert_resource(self.assets.registry.clone());
+                for on_reload in &self.hot_reload.on_assets {
+                    on_reload(world);
+                }
+            }
+            for (old, new) in &fonts {
+                if world.resource::<FontResource>().0 == *old {
+                    world.insert_resource(FontResource(*new));
+                }
+                for mut text in world.query::<&mut FontComponent>().iter_mut(world) {
+                    if text.font == *old {
+                        text.font = *new;
+                    }
+                }
+            }
+        }
+        for on_change in self.hot_reload.changed_files() {
+            for scene in &mut self.scenes {
+                on_change(&mut scene.world);
+            }
+        }
+        self.hot_reload.add_reload_time((get_time() - start) as f32);
+    }
+
+    /// Length of the previous frame, leaving out time the dev build spent reloading.
+    fn frame_time(&mut self) -> f32 {
+        #[cfg(feature = "dev")]
+        return get_frame_time() - self.hot_reload.take_reload_time();
+        #[cfg(not(feature = "dev"))]
+        get_frame_time()
+    }
+
+    fn pop_scene(&mut self) {
+        if let Some(mut scene) = self.scenes.pop() {
+            for on_exit in &self.on_exit {
+                on_exit(&mut scene.world);
+            }
+        }
+    }
+
+    fn apply(&mut self, transition: SceneTransition) {
+        match transition {
+            SceneTransition::Push(factory) => self.scenes.push(factory(&self.assets)),
+            SceneTransition::Pop(callback) => {
+                self.pop_scene();
+                if let Some(scene) = self.scenes.last_mut() {
+                    if let Some(on_resume) = scene.on_resume {
+                        on_resume(&mut scene.world);
+                    }
+                    if let Some(callback) = callback {
+                        callback(&mut scene.world);
+                    }
+                    if let Some(next) = scene.take_request() {
+                        self.apply(next);
+                    }
+                }
+            }
+            SceneTransition::Replace(factory) => {
+                self.pop_scene();
+                self.scenes.push(factory(&self.assets));
+            }
+            SceneTransition::Reset(factory) => {
+                while !self.scenes.is_empty() {
+                    self.pop_scene();
+                }
+                self.scenes.push(factory(&self.assets));
+            }
+            SceneTransition::Quit => {
+                while !self.scenes.is_empty() {
+                    self.pop_scene();
+                }
+                self.quit = true;
+            }
+        }
+    }
+}
+
+impl World for SceneStack {
+    fn start(&mut self) -> GameResult<()> {
+        if let Some(initial) = self.initial.take() {
+            self.scenes.push(initial(&self.assets));
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, delta: f32) -> GameResult<()> {
+        let top = match self.scenes.len() {
+            0 => return Ok(()),
+            len => len - 1,
+        };
+        let bottom = self.scenes.iter().rposition(|s| !s.overlay).unwrap_or(0);
+
+        let camera = self.assets.resolution.camera;
+        set_camera(&camera);
+        clear_background(BLACK);
+        for scene in &mut self.scenes[bottom..top] {
+            scene.draw(&camera);
+        }
+        let frame_time = self.frame_time();
+        let scene = &mut self.scenes[top];
+        let mouse = self.viewport.mouse_position(&self.assets.resolution);
+        scene.world.insert_resource(MousePosition(mouse));
+        scene.world.insert_resource(FrameTime(frame_time));
+        scene.world.insert_resource(std::mem::take(&mut self.audio));
+        scene
+            .world
+            .insert_resource(std::mem::take(&mut self.viewport));
+        scene.update(delta);
+        scene.draw(&camera);
+        self.audio = scene
+            .world
+            .remove_resource::<AudioManager>()
+            .unwrap_or_default();
+        self.viewport = scene
+            .world
+            .remove_resource::<Viewport>()
+            .unwrap_or_default();
+        self.audio.update(delta, &self.assets.registry);
+        set_default_camera();
+        self.viewport.draw(&self.assets.resolution);
+
+        if let Some(transition) = self.scenes[top].take_request() {
+            self.apply(transition);
+        }
+        Ok(())
+    }
+
+    fn should_quit(&self) -> bool {
+        self.quit || self.scenes.is_empty()
+    }
+}
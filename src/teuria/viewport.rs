@@ -0,0 +1,116 @@
+use macroquad::{
+    prelude::{mouse_position, vec2, Color, Rect, Vec2, DARKGRAY, WHITE},
+    texture::{draw_texture_ex, DrawTextureParams, FilterMode, Texture2D},
+    window::{clear_background, screen_height, screen_width},
+};
+use macroquad_canvas::Canvas2D;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScaleMode {
+    /// Largest whole-number scale that fits, so every canvas pixel stays square.
+    PixelPerfect,
+    /// Largest scale that fits while keeping the aspect ratio.
+    Letterbox,
+    /// Fills the window, distorting the aspect ratio if needed.
+    Stretch,
+}
+
+impl ScaleMode {
+    pub const ALL: [ScaleMode; 3] = [
+        ScaleMode::PixelPerfect,
+        ScaleMode::Letterbox,
+        ScaleMode::Stretch,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScaleMode::PixelPerfect => "Pixel Perfect",
+            ScaleMode::Letterbox => "Letterbox",
+            ScaleMode::Stretch => "Stretch",
+        }
+    }
+}
+
+/// Mouse position in canvas coordinates, refreshed for the top scene every frame.
+#[derive(Default)]
+pub struct MousePosition(pub Vec2);
+
+/// How the canvas is fitted into the window and what fills the bars around it.
+pub struct Viewport {
+    pub mode: ScaleMode,
+    pub bar_color: Color,
+    /// Stretched over the whole window behind the canvas instead of the bar color.
+    pub bar_image: Option<Texture2D>,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            mode: ScaleMode::Letterbox,
+            bar_color: DARKGRAY,
+            bar_image: None,
+        }
+    }
+}
+
+impl Viewport {
+    /// Where the canvas lands on a screen of the given size.
+    pub fn rect(&self, canvas: &Canvas2D, screen: Vec2) -> Rect {
+        let size = vec2(canvas.width(), canvas.height());
+        let scale = screen / size;
+        let scale = match self.mode {
+            ScaleMode::Stretch => return Rect::new(0.0, 0.0, screen.x, screen.y),
+            ScaleMode::Letterbox => scale.min_element(),
+            // Windows smaller than the canvas fall back to shrinking it.
+            ScaleMode::PixelPerfect => match scale.min_element() {
+                s if s >= 1.0 => s.floor(),
+                s => s,
+            },
+        };
+        let dest = size * scale;
+        let padding = (screen - dest) * 0.5;
+        Rect::new(padding.x, padding.y, dest.x, dest.y)
+    }
+
+    pub fn draw(&self, canvas: &Canvas2D) {
+        let screen = vec2(screen_width(), screen_height());
+        clear_background(self.bar_color);
+        if let Some(image) = self.bar_image {
+            draw_texture_ex(
+                image,
+                0.0,
+                0.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(screen),
+                    ..Default::default()
+                },
+            );
+        }
+        let rect = self.rect(canvas, screen);
+        let texture = *canvas.get_texture();
+        texture.set_filter(match self.mode {
+            ScaleMode::PixelPerfect => FilterMode::Nearest,
+            _ => FilterMode::Linear,
+        });
+        draw_texture_ex(
+            texture,
+            rect.x,
+            rect.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(rect.size()),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Mouse position in canvas coordinates, clamped to the canvas.
+    pub fn mouse_position(&self, canvas: &Canvas2D) -> Vec2 {
+        let rect = self.rect(canvas, vec2(screen_width(), screen_height()));
+        let size = vec2(canvas.width(), canvas.height());
+        let mouse = Vec2::from(mouse_position());
+        ((mouse - rect.point()) / rect.size() * size).clamp(Vec2::ZERO, size)
+    }
+}
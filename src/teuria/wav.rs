@@ -0,0 +1,194 @@
+use std::fmt::{self, Display};
+
+const FORMAT_PCM: u16 = 1;
+
+#[derive(Debug)]
+pub struct WavError(pub String);
+
+impl Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid wav file: {}", self.0)
+    }
+}
+
+impl std::error::Error for WavError {}
+
+struct Format {
+    channels: usize,
+    bits: u16,
+}
+
+impl Format {
+    fn sample(&self, bytes: &[u8]) -> f32 {
+        match self.bits {
+            8 => bytes[0] as f32 - 128.0,
+            _ => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+        }
+    }
+
+    fn write(&self, sample: f32, out: &mut Vec<u8>) {
+        match self.bits {
+            8 => out.push((sample.round() + 128.0).clamp(0.0, 255.0) as u8),
+            _ => out.extend((sample.round().clamp(-32768.0, 32767.0) as i16).to_le_bytes()),
+        }
+    }
+}
+
+fn word(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn dword(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Resamples an 8 or 16-bit PCM file so it plays `pitch` times higher and shorter
+/// at its original sample rate. Chunks other than the format and data are dropped.
+pub fn repitch(bytes: &[u8], pitch: f32) -> Result<Vec<u8>, WavError> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WavError("not a RIFF/WAVE file".into()));
+    }
+    if !(pitch > 0.0 && pitch.is_finite()) {
+        return Err(WavError(format!("cannot change pitch by {pitch}")));
+    }
+    let mut fmt = None;
+    let mut data = None;
+    let mut position = 12;
+    while position + 8 <= bytes.len() {
+        let id = &bytes[position..position + 4];
+        let size = dword(bytes, position + 4) as usize;
+        let body = bytes
+            .get(position + 8..position + 8 + size)
+            .ok_or_else(|| WavError("unexpected end of file".into()))?;
+        match id {
+            b"fmt " => fmt = Some(body),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        position += 8 + size + size % 2;
+    }
+    let fmt = fmt.ok_or_else(|| WavError("no format chunk".into()))?;
+    let data = data.ok_or_else(|| WavError("no data chunk".into()))?;
+    if fmt.len() < 16 {
+        return Err(WavError("format chunk too short".into()));
+    }
+    let format = Format {
+        channels: word(fmt, 2) as usize,
+        bits: word(fmt, 14),
+    };
+    if word(fmt, 0) != FORMAT_PCM || !matches!(format.bits, 8 | 16) || format.channels == 0 {
+        return Err(WavError(format!(
+            "only 8 and 16-bit PCM is supported, not format {} at {} bits",
+            word(fmt, 0),
+            format.bits
+        )));
+    }
+
+    let frame_size = format.channels * format.bits as usize / 8;
+    let frames = data
+        .chunks_exact(frame_size)
+        .map(|frame| {
+            frame
+                .chunks_exact(frame_size / format.channels)
+                .map(|sample| format.sample(sample))
+                .collect::<Vec<f32>>()
+        })
+        .collect::<Vec<Vec<f32>>>();
+    let length = (frames.len() as f32 / pitch) as usize;
+    let mut samples = Vec::with_capacity(length * frame_size);
+    for i in 0..length {
+        let source = i as f32 * pitch;
+        let index = (source as usize).min(frames.len() - 1);
+        let t = source.fract();
+        let next = frames.get(index + 1).unwrap_or(&frames[index]);
+        for (a, b) in frames[index].iter().zip(next) {
+            format.write(a + (b - a) * t, &mut samples);
+        }
+    }
+
+    let mut out = Vec::with_capacity(44 + samples.len());
+    out.extend(b"RIFF");
+    out.extend((36 + samples.len() as u32).to_le_bytes());
+    out.extend(b"WAVEfmt ");
+    out.extend(16u32.to_le_bytes());
+    out.extend(&fmt[..16]);
+    out.extend(b"data");
+    out.extend((samples.len() as u32).to_le_bytes());
+    out.extend(samples);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::repitch;
+
+    fn wav(channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block = channels * bits / 8;
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend((36 + data.len() as u32).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(channels.to_le_bytes());
+        bytes.extend(22050u32.to_le_bytes());
+        bytes.extend((22050 * block as u32).to_le_bytes());
+        bytes.extend(block.to_le_bytes());
+        bytes.extend(bits.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend((data.len() as u32).to_le_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn same_pitch_keeps_samples() {
+        let input = wav(1, 8, &[128, 200, 56, 128]);
+        assert_eq!(repitch(&input, 1.0).unwrap(), input);
+    }
+
+    #[test]
+    fn higher_pitch_skips_samples() {
+        let output = repitch(&wav(1, 8, &[0, 10, 20, 30, 40, 50]), 2.0).unwrap();
+        assert_eq!(output, wav(1, 8, &[0, 20, 40]));
+    }
+
+    #[test]
+    fn lower_pitch_interpolates_each_channel() {
+        let data = [0i16, -100, 100, 100]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect::<Vec<u8>>();
+        let expected = [0i16, -100, 50, 0, 100, 100, 100, 100]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect::<Vec<u8>>();
+        let output = repitch(&wav(2, 16, &data), 0.5).unwrap();
+        assert_eq!(output, wav(2, 16, &expected));
+    }
+
+    #[test]
+    fn skips_unknown_chunks() {
+        let mut input = wav(1, 8, &[1, 2]);
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), &[7, 7, 7, 0]].concat();
+        input.splice(12..12, list);
+        assert_eq!(repitch(&input, 1.0).unwrap(), wav(1, 8, &[1, 2]));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(repitch(b"OggS\0\0\0\0\0\0\0\0", 1.0).is_err());
+        let mut float = wav(1, 16, &[0, 0]);
+        float[20] = 3;
+        assert!(repitch(&float, 1.0).is_err());
+        let truncated = wav(1, 8, &[1, 2, 3]);
+        assert!(repitch(&truncated[..truncated.len() - 1], 1.0).is_err());
+    }
+
+    #[test]
+    fn reads_the_hit_sound() {
+        let bytes = std::fs::read("res/hitblock.wav").unwrap();
+        let output = repitch(&bytes, 1.25).unwrap();
+        assert_eq!(output.len(), 44 + 720 * 4 / 5);
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
+};
+
+use macroquad::prelude::{warn, Color};
+use serde::{Deserialize, Serialize};
+
+pub const THEMES_DIR: &str = "res/themes";
+/// The theme made of the assets directly in `res/`.
+pub const DEFAULT_THEME: &str = "Default";
+pub const PALETTE_FILE: &str = "palette.toml";
+
+static ACTIVE: OnceLock<Theme> = OnceLock::new();
+
+/// Colors a theme may swap into the default palette; anything left out keeps its usual color.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemePalette {
+    pub fragile: Option<[u8; 3]>,
+    pub cloner: Option<[u8; 3]>,
+    pub special: Option<[u8; 3]>,
+    pub healthy: Option<[u8; 3]>,
+    pub damaged: Option<[u8; 3]>,
+    pub critical: Option<[u8; 3]>,
+    pub ball: Option<[u8; 3]>,
+    pub clone_ball: Option<[u8; 3]>,
+    pub special_ball: Option<[u8; 3]>,
+    pub player: Option<[u8; 3]>,
+}
+
+pub fn color([r, g, b]: [u8; 3]) -> Color {
+    Color::from_rgba(r, g, b, 255)
+}
+
+/// A skin pack under `res/themes/<name>/`. Any of `player.png`, `ball.png`, `blocks.png`,
+/// `background.png`, `font.ttf` and `palette.toml` may be left out to keep the default.
+pub struct Theme {
+    dir: Option<PathBuf>,
+    /// Locked so the dev build can reload it.
+    palette: RwLock<ThemePalette>,
+}
+
+impl Theme {
+    pub fn load(name: &str) -> Theme {
+        let dir = Path::new(THEMES_DIR).join(name);
+        if name == DEFAULT_THEME || !dir.is_dir() {
+            if name != DEFAULT_THEME {
+                warn!("Theme {} not found, using the default", name);
+            }
+            return Theme {
+                dir: None,
+                palette: RwLock::default(),
+            };
+        }
+        Theme {
+            palette: RwLock::new(read_palette(&dir)),
+            dir: Some(dir),
+        }
+    }
+
+    pub fn palette(&self) -> ThemePalette {
+        *self.palette.read().unwrap()
+    }
+
+    /// Directory whose files replace the `skin` entries of the asset manifest.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+}
+
+fn read_palette(dir: &Path) -> ThemePalette {
+    fs::read_to_string(dir.join(PALETTE_FILE))
+        .ok()
+        .and_then(|contents| match toml::from_str(&contents) {
+            Ok(palette) => Some(palette),
+            Err(e) => {
+                warn!("Ignoring palette of theme {}: {}", dir.display(), e);
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Rereads the active theme's palette and has the world recolor itself with it.
+#[cfg(feature = "dev")]
+pub fn reload_palette(world: &mut bevy_ecs::world::World) {
+    let theme = active();
+    if let Some(dir) = theme.dir() {
+        *theme.palette.write().unwrap() = read_palette(dir);
+    }
+    // Settings changes are what make `palette::system` rebuild the palette.
+    if let Some(mut settings) = world.get_resource_mut::<crate::settings::Settings>() {
+        bevy_ecs::change_detection::DetectChanges::set_changed(&mut settings);
+    }
+}
+
+/// Names of every installed theme, starting with the default.
+pub fn available() -> Vec<String> {
+    let mut themes = fs::read_dir(THEMES_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    themes.sort();
+    themes.retain(|name| name != DEFAULT_THEME);
+    themes.insert(0, DEFAULT_THEME.into());
+    themes
+}
+
+/// Loads the theme used for the rest of the session; switching themes needs a restart.
+pub fn activate(name: &str) -> &'static Theme {
+    ACTIVE.get_or_init(|| Theme::load(name))
+}
+
+pub fn active() -> &'static Theme {
+    ACTIVE.get_or_init(|| Theme::load(DEFAULT_THEME))
+}